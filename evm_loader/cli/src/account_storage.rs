@@ -1,12 +1,16 @@
 use std::{
     cell::RefCell,
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     rc::Rc,
     convert::TryInto,
 };
 
 use log::{info, trace, warn};
 use evm::{H160, U256, H256};
+use solana_client::{
+    client_error::Result as ClientResult,
+    rpc_request::MAX_MULTIPLE_ACCOUNTS,
+};
 use solana_sdk::{
     account::Account,
     account_info::AccountInfo,
@@ -22,10 +26,18 @@ use evm_loader::{
 };
 
 
-use crate::Config;
+use crate::{Config, errors::NeonCliError, metrics, rpc, session::{Session, SessionAccount}};
 
 const FAKE_OPERATOR: Pubkey = pubkey!("neonoperator1111111111111111111111111111111");
 
+/// Fetches a Solana account, it is counted as downloaded only if received
+fn download_account(config: &Config, key: &Pubkey) -> ClientResult<Account> {
+    let account = rpc::retry(|| config.rpc_client.get_account(key))?;
+    metrics::account_downloaded();
+
+    Ok(account)
+}
+
 fn serde_pubkey_bs58<S>(value: &Pubkey, s: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
     let bs58 = bs58::encode(value).into_string();
     s.serialize_str(&bs58)
@@ -61,7 +73,7 @@ impl NeonAccount {
         let (key, _) = make_solana_program_address(&address, &config.evm_loader);
         info!("get_account_from_solana 0x{} => {}", address, key);
 
-        if let Ok(mut account) = config.rpc_client.get_account(&key) {
+        if let Ok(mut account) = download_account(config, &key) {
             trace!("Account found");
 
             let code_key = {
@@ -71,7 +83,7 @@ impl NeonAccount {
             };
 
             let code_account = code_key.map(|code_key| {
-                config.rpc_client.get_account(&code_key).unwrap() // Something is seriously wrong if it panic
+                download_account(config, &code_key).unwrap() // Something is seriously wrong if it panic
             });
 
             let code_size = code_account.as_ref()
@@ -118,36 +130,68 @@ pub struct SolanaAccount {
 pub struct EmulatorAccountStorage<'a> {
     pub accounts: RefCell<HashMap<H160, NeonAccount>>,
     pub solana_accounts: RefCell<HashMap<Pubkey, SolanaAccount>>,
+    solana_accounts_data: RefCell<HashMap<Pubkey, Rc<Account>>>,
     config: &'a Config,
     block_number: u64,
     block_timestamp: i64,
     neon_token_mint: Pubkey,
     chain_id: u64,
+    session: BTreeMap<H160, SessionAccount>,
 }
 
 impl<'a> EmulatorAccountStorage<'a> {
     pub fn new(config: &'a Config, token_mint: Pubkey, chain_id: u64) -> EmulatorAccountStorage {
         trace!("backend::new");
 
-        let slot = config.rpc_client.get_slot().unwrap_or_default();
-        let timestamp = config.rpc_client.get_block_time(slot).unwrap_or_default();
+        let slot = rpc::retry(|| config.rpc_client.get_slot()).unwrap_or_default();
+        let timestamp = rpc::retry(|| config.rpc_client.get_block_time(slot)).unwrap_or_default();
 
         Self {
             accounts: RefCell::new(HashMap::new()),
             solana_accounts: RefCell::new(HashMap::new()),
+            solana_accounts_data: RefCell::new(HashMap::new()),
             config,
             block_number: slot,
             block_timestamp: timestamp,
             neon_token_mint: token_mint,
             chain_id,
+            session: BTreeMap::new(),
+        }
+    }
+
+    /// Emulates on top of the state changed by previous emulations of the session
+    #[must_use]
+    pub fn with_session(mut self, session: &Session) -> Self {
+        self.session = session.accounts.clone();
+        self
+    }
+
+    /// Solana accounts required to execute the emulated transaction and whether they are writable
+    #[must_use]
+    pub fn used_solana_accounts(&self) -> BTreeMap<Pubkey, bool> {
+        let mut used: BTreeMap<Pubkey, bool> = BTreeMap::new();
+        let mut add = |pubkey: Pubkey, is_writable: bool| {
+            *used.entry(pubkey).or_default() |= is_writable;
+        };
+
+        for account in self.accounts.borrow().values() {
+            add(account.account, account.writable);
+            if let Some(contract) = account.contract {
+                add(contract, account.writable);
+            }
+        }
+        for account in self.solana_accounts.borrow().values() {
+            add(account.pubkey, account.is_writable);
         }
+
+        used
     }
 
     pub fn get_account_from_solana(config: &'a Config, address: &H160) -> Option<(Account, Option<Account>)> {
         let (solana_address, _solana_nonce) = make_solana_program_address(address, &config.evm_loader);
         info!("get_account_from_solana 0x{} => {}", address, solana_address);
 
-        if let Ok(mut acc) = config.rpc_client.get_account(&solana_address) {
+        if let Ok(mut acc) = download_account(config, &solana_address) {
             trace!("Account found");
             trace!("Account data len {}", acc.data.len());
             trace!("Account owner {}", acc.owner);
@@ -160,7 +204,7 @@ impl<'a> EmulatorAccountStorage<'a> {
 
             let code_account = if let Some(code_address) = code_address {
                 info!("code_account == {}", code_address);
-                config.rpc_client.get_account(&code_address).ok()
+                download_account(config, &code_address).ok()
             } else {
                 info!("code_account == None");
                 None
@@ -194,6 +238,19 @@ impl<'a> EmulatorAccountStorage<'a> {
         }
     }
 
+    /// Downloads Solana account once per emulation, subsequent reads reuse the download.
+    /// Callers still receive owned copies, `AccountStorage` returns `OwnedAccountInfo` with its own data.
+    fn get_solana_account(&self, address: &Pubkey) -> Rc<Account> {
+        let mut accounts_data = self.solana_accounts_data.borrow_mut();
+
+        let account = accounts_data.entry(*address).or_insert_with(|| {
+            let account = download_account(self.config, address).unwrap_or_default();
+            Rc::new(account)
+        });
+
+        Rc::clone(account)
+    }
+
     fn add_solana_account(&self, pubkey: Pubkey, is_writable: bool) {
         if solana_sdk::system_program::check_id(&pubkey) {
             return;
@@ -332,7 +389,7 @@ impl<'a> AccountStorage for EmulatorAccountStorage<'a> {
             return H256::default();
         }
 
-        if let Ok(timestamp) = self.config.rpc_client.get_block(number.as_u64()) {
+        if let Ok(timestamp) = rpc::retry(|| self.config.rpc_client.get_block(number.as_u64())) {
             H256::from_slice(&bs58::decode(timestamp.blockhash).into_vec().unwrap())
         } else {
             warn!("Got error trying to get block hash");
@@ -346,24 +403,39 @@ impl<'a> AccountStorage for EmulatorAccountStorage<'a> {
         self.add_ethereum_account(address, false);
 
         let accounts = self.accounts.borrow();
-        accounts.contains_key(address)
+        accounts.contains_key(address) || self.session.contains_key(address)
     }
 
     fn nonce(&self, address: &H160) -> U256 {
         info!("nonce {}", address);
 
+        if let Some(account) = self.session.get(address) {
+            self.add_ethereum_account(address, false);
+            return account.nonce;
+        }
+
         self.ethereum_account_map_or(address, 0_u64, |a| a.trx_count).into()
     }
 
     fn balance(&self, address: &H160) -> U256 {
         info!("balance {}", address);
 
+        if let Some(account) = self.session.get(address) {
+            self.add_ethereum_account(address, false);
+            return account.balance;
+        }
+
         self.ethereum_account_map_or(address, U256::zero(), |a| a.balance)
     }
 
     fn code_size(&self, address: &H160) -> usize {
         info!("code_size {}", address);
 
+        if let Some(account) = self.session.get(address) {
+            self.add_ethereum_account(address, false);
+            return account.code.len();
+        }
+
         self.ethereum_contract_map_or(address, 0_u32, |c| c.code_size)
             .try_into()
             .expect("usize is 8 bytes")
@@ -372,6 +444,11 @@ impl<'a> AccountStorage for EmulatorAccountStorage<'a> {
     fn code_hash(&self, address: &H160) -> H256 {
         info!("code_hash {}", address);
 
+        if let Some(account) = self.session.get(address) {
+            self.add_ethereum_account(address, false);
+            return evm_loader::utils::keccak256_h256(&account.code);
+        }
+
         self.ethereum_contract_map_or(address, 
             H256::default(), 
             |c| evm_loader::utils::keccak256_h256(&c.extension.code)
@@ -381,6 +458,11 @@ impl<'a> AccountStorage for EmulatorAccountStorage<'a> {
     fn code(&self, address: &H160) -> Vec<u8> {
         info!("code {}", address);
 
+        if let Some(account) = self.session.get(address) {
+            self.add_ethereum_account(address, false);
+            return account.code.clone();
+        }
+
         self.ethereum_contract_map_or(address,
             Vec::new(),
             |c| c.extension.code.to_vec()
@@ -390,6 +472,11 @@ impl<'a> AccountStorage for EmulatorAccountStorage<'a> {
     fn valids(&self, address: &H160) -> Vec<u8> {
         info!("valids {}", address);
 
+        if let Some(account) = self.session.get(address) {
+            self.add_ethereum_account(address, false);
+            return account.valids.clone();
+        }
+
         self.ethereum_contract_map_or(address,
             Vec::new(),
             |c| c.extension.valids.to_vec()
@@ -411,6 +498,15 @@ impl<'a> AccountStorage for EmulatorAccountStorage<'a> {
     fn storage(&self, address: &H160, index: &U256) -> U256 {
         info!("storage {} -> {}", address, index);
 
+        if let Some(account) = self.session.get(address) {
+            if let Some(value) = account.storage.get(index) {
+                return *value;
+            }
+            if account.destroyed {
+                return U256::zero();
+            }
+        }
+
         let value = if *index < U256::from(STORAGE_ENTIRIES_IN_CONTRACT_ACCOUNT) {
             let index: usize = index.as_usize() * 32;
             self.ethereum_contract_map_or(address,
@@ -422,8 +518,8 @@ impl<'a> AccountStorage for EmulatorAccountStorage<'a> {
             info!("read storage solana address {:?} - {:?}", address, solana_address);
 
             self.add_solana_account(solana_address, false);
-        
-            if let Ok(mut account) = self.config.rpc_client.get_account(&solana_address) {
+
+            if let Ok(mut account) = download_account(self.config, &solana_address) {
                 if solana_sdk::system_program::check_id(&account.owner) {
                     info!("read storage system owned");
                     U256::zero()
@@ -487,22 +583,36 @@ impl<'a> AccountStorage for EmulatorAccountStorage<'a> {
         } else {
             self.add_solana_account(*address, false);
 
-            let mut account = self.config.rpc_client.get_account(address).unwrap_or_default();
-            let info = account_info(address, &mut account);
-    
-            OwnedAccountInfo::from_account_info(&info)
+            let account = self.get_solana_account(address);
+
+            OwnedAccountInfo {
+                key: *address,
+                is_signer: false,
+                is_writable: false,
+                lamports: account.lamports,
+                data: account.data.clone(),
+                owner: account.owner,
+                executable: account.executable,
+                rent_epoch: account.rent_epoch,
+            }
         }
     }
 
     fn clone_solana_account_partial(&self, address: &Pubkey, offset: usize, len: usize) -> Option<OwnedAccountInfoPartial> {
         info!("clone_solana_account_partial {}", address);
 
-        let account = self.clone_solana_account(address);
+        if address == &FAKE_OPERATOR {
+            return None; // operator has no data
+        }
+
+        self.add_solana_account(*address, false);
+
+        let account = self.get_solana_account(address);
 
         Some(OwnedAccountInfoPartial {
-            key: account.key,
-            is_signer: account.is_signer,
-            is_writable: account.is_writable,
+            key: *address,
+            is_signer: false,
+            is_writable: false,
             lamports: account.lamports,
             data: account.data.get(offset .. offset + len).map(<[u8]>::to_vec)?,
             data_offset: offset,
@@ -541,4 +651,21 @@ pub fn make_solana_program_address(
     program_id: &Pubkey
 ) -> (Pubkey, u8) {
     Pubkey::find_program_address(&[&[ACCOUNT_SEED_VERSION], ether_address.as_bytes()], program_id)
-}
\ No newline at end of file
+}
+
+/// Fetches accounts with `getMultipleAccounts` in chunks allowed by RPC.
+/// Missing accounts are not included in the result.
+pub fn get_multiple_accounts(config: &Config, keys: &[Pubkey]) -> Result<HashMap<Pubkey, Account>, NeonCliError> {
+    let mut accounts = HashMap::new();
+    for chunk in keys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let chunk_accounts = config.rpc_client.get_multiple_accounts(chunk)?;
+        for (key, account) in chunk.iter().zip(chunk_accounts) {
+            if let Some(account) = account {
+                metrics::account_downloaded();
+                accounts.insert(*key, account);
+            }
+        }
+    }
+
+    Ok(accounts)
+}
@@ -1,7 +1,6 @@
 use log::{info};
 
 use solana_sdk::{
-    incinerator,
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     compute_budget::ComputeBudgetInstruction,
@@ -13,7 +12,8 @@ use evm_loader::{
         COMPUTE_BUDGET_UNITS,
         COMPUTE_BUDGET_HEAP_FRAME,
         REQUEST_UNITS_ADDITIONAL_FEE,
-    }
+    },
+    instruction_builder,
 };
 
 use crate::{
@@ -21,6 +21,8 @@ use crate::{
         make_solana_program_address,
         account_info,
     },
+    errors::NeonCliError,
+    transaction_signer::send_instructions,
     Config,
     NeonCliResult,
 };
@@ -34,17 +36,25 @@ pub fn execute(
     let storage_info = account_info(storage_account, &mut acc);
     let storage = State::from_account(&config.evm_loader, &storage_info)?;
 
+    let instructions = cancel_instructions(config, storage_account, &storage)?;
+
+    send_instructions(config, &instructions, config.rpc_client.commitment())?;
+
+    Ok(())
+}
+
+/// Instructions cancelling the transaction stored in the state account on behalf of the config signer
+pub fn cancel_instructions(
+    config: &Config,
+    storage_account: &Pubkey,
+    storage: &State,
+) -> Result<Vec<Instruction>, NeonCliError> {
     let (caller_solana, _) = make_solana_program_address(&storage.caller, &config.evm_loader);
     let (trx_count, _caller_ether) = crate::get_ether_account_nonce(config, &caller_solana)?;
 
     let operator = &config.signer.pubkey();
 
-    let mut accounts_meta : Vec<AccountMeta> = vec![
-        AccountMeta::new(*storage_account, false),              // State account
-        AccountMeta::new(*operator, true),                      // Operator
-        AccountMeta::new(incinerator::id(), false),             // Incinerator
-    ];
-
+    let mut accounts_meta : Vec<AccountMeta> = Vec::new();
     let remaining_accounts = storage.accounts()?;
     for (writable, key) in remaining_accounts {
         if writable {
@@ -57,18 +67,14 @@ pub fn execute(
         info!("\t{:?}", meta);
     }
 
-    let cancel_with_nonce_instruction = Instruction::new_with_bincode(
-        config.evm_loader, &(21_u8, trx_count), accounts_meta
+    let cancel_with_nonce_instruction = instruction_builder::cancel_with_nonce(
+        &config.evm_loader, storage_account, operator, trx_count, &accounts_meta
     );
 
-    let instructions = vec![
+    Ok(vec![
         ComputeBudgetInstruction::request_units(COMPUTE_BUDGET_UNITS, REQUEST_UNITS_ADDITIONAL_FEE),
         ComputeBudgetInstruction::request_heap_frame(COMPUTE_BUDGET_HEAP_FRAME),
         cancel_with_nonce_instruction
-    ];
-
-    crate::send_transaction(config, &instructions)?;
-
-    Ok(())
+    ])
 }
 
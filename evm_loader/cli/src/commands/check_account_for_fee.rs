@@ -0,0 +1,44 @@
+use log::{debug, info};
+
+use solana_sdk::message::Message;
+
+use crate::{
+    operators,
+    Config,
+    NeonCliResult,
+};
+
+
+pub fn execute(
+    config: &Config,
+) -> NeonCliResult {
+    debug!("command_check_account_for_fee(config={:?})", config);
+
+    // Fee of a transaction with the only signature of the fee payer
+    let signature_fee = config.rpc_client.get_fee_for_message(&Message::new(&[], Some(&config.signer.pubkey())))?;
+
+    let mut total = 0_u64;
+    let mut balances = Vec::new();
+    for fee_payer in operators::fee_payers(config) {
+        let pubkey = fee_payer.pubkey();
+        let balance = config.rpc_client.get_balance(&pubkey)?;
+        info!("fee payer {}: balance {}", pubkey, balance);
+
+        total = total.saturating_add(balance);
+        balances.push(serde_json::json!({
+            "pubkey": pubkey.to_string(),
+            "balance": balance,
+            "sufficient": balance >= signature_fee,
+        }));
+    }
+
+    let js = serde_json::json!({
+        "signature_fee": signature_fee,
+        "total_balance": total,
+        "fee_payers": balances,
+    });
+
+    println!("{}", js);
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet},
+};
+
+use log::debug;
+
+use evm::{H160, H256, U256};
+use evm_loader::{
+    account_storage::AccountStorage,
+    executor::{OwnedAccountInfo, OwnedAccountInfoPartial},
+};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    account_storage::EmulatorAccountStorage,
+    commands::estimate_gas,
+    syscall_stubs::Stubs,
+    Config,
+    NeonCliResult,
+};
+
+
+/// Records Ethereum accounts and storage slots read by the emulation
+struct RecordingAccountStorage<'a, B: AccountStorage> {
+    backend: &'a B,
+    touched: RefCell<BTreeMap<H160, BTreeSet<U256>>>,
+}
+
+impl<'a, B: AccountStorage> RecordingAccountStorage<'a, B> {
+    fn new(backend: &'a B) -> Self {
+        Self { backend, touched: RefCell::new(BTreeMap::new()) }
+    }
+
+    fn touch(&self, address: &H160) {
+        self.touched.borrow_mut().entry(*address).or_default();
+    }
+
+    /// EIP-2930 access list in the `eth_createAccessList` format
+    fn access_list(&self) -> serde_json::Value {
+        let touched = self.touched.borrow();
+
+        touched.iter()
+            .map(|(address, keys)| serde_json::json!({
+                "address": format!("0x{}", hex::encode(address.as_bytes())),
+                "storageKeys": keys.iter()
+                    .map(|key| {
+                        let mut bytes = [0_u8; 32];
+                        key.to_big_endian(&mut bytes);
+                        format!("0x{}", hex::encode(bytes))
+                    })
+                    .collect::<Vec<_>>(),
+            }))
+            .collect()
+    }
+}
+
+impl<'a, B: AccountStorage> AccountStorage for RecordingAccountStorage<'a, B> {
+    fn neon_token_mint(&self) -> &Pubkey { self.backend.neon_token_mint() }
+    fn program_id(&self) -> &Pubkey { self.backend.program_id() }
+    fn operator(&self) -> &Pubkey { self.backend.operator() }
+
+    fn block_number(&self) -> U256 { self.backend.block_number() }
+    fn block_timestamp(&self) -> U256 { self.backend.block_timestamp() }
+    fn block_hash(&self, number: U256) -> H256 { self.backend.block_hash(number) }
+    fn chain_id(&self) -> u64 { self.backend.chain_id() }
+
+    fn exists(&self, address: &H160) -> bool {
+        self.touch(address);
+        self.backend.exists(address)
+    }
+
+    fn nonce(&self, address: &H160) -> U256 {
+        self.touch(address);
+        self.backend.nonce(address)
+    }
+
+    fn balance(&self, address: &H160) -> U256 {
+        self.touch(address);
+        self.backend.balance(address)
+    }
+
+    fn code_size(&self, address: &H160) -> usize {
+        self.touch(address);
+        self.backend.code_size(address)
+    }
+
+    fn code_hash(&self, address: &H160) -> H256 {
+        self.touch(address);
+        self.backend.code_hash(address)
+    }
+
+    fn code(&self, address: &H160) -> Vec<u8> {
+        self.touch(address);
+        self.backend.code(address)
+    }
+
+    fn valids(&self, address: &H160) -> Vec<u8> {
+        self.touch(address);
+        self.backend.valids(address)
+    }
+
+    fn generation(&self, address: &H160) -> u32 {
+        self.backend.generation(address)
+    }
+
+    fn get_storage_address(&self, address: &H160, index: &U256) -> (Pubkey, u8) {
+        self.backend.get_storage_address(address, index)
+    }
+
+    fn storage(&self, address: &H160, index: &U256) -> U256 {
+        self.touched.borrow_mut().entry(*address).or_default().insert(*index);
+        self.backend.storage(address, index)
+    }
+
+    fn clone_solana_account(&self, address: &Pubkey) -> OwnedAccountInfo {
+        self.backend.clone_solana_account(address)
+    }
+
+    fn clone_solana_account_partial(&self, address: &Pubkey, offset: usize, len: usize) -> Option<OwnedAccountInfoPartial> {
+        self.backend.clone_solana_account_partial(address, offset, len)
+    }
+
+    fn solana_address(&self, address: &H160) -> (Pubkey, u8) {
+        self.backend.solana_address(address)
+    }
+
+    fn solana_accounts_space(&self, address: &H160) -> (usize, usize) {
+        self.backend.solana_accounts_space(address)
+    }
+}
+
+
+/// Emulates the transaction as `estimate-gas` does and prints the accounts and storage slots it reads.
+/// The sender and the target are listed too, Solana accounts of them are required as well.
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    config: &Config,
+    contract_id: Option<H160>,
+    caller_id: H160,
+    data: Option<Vec<u8>>,
+    value: Option<U256>,
+    token_mint: &Pubkey,
+    chain_id: u64,
+    max_steps_to_execute: u64,
+) -> NeonCliResult {
+    debug!("command_create_access_list(config={:?}, contract_id={:?}, caller_id={:?}, data={:?}, value={:?})",
+        config,
+        contract_id,
+        caller_id,
+        &hex::encode(data.clone().unwrap_or_default()),
+        value);
+
+    solana_sdk::program_stubs::set_syscall_stubs(Stubs::new(config)?);
+
+    let storage = EmulatorAccountStorage::new(config, *token_mint, chain_id);
+    let recorder = RecordingAccountStorage::new(&storage);
+
+    let (result, exit_reason, steps_executed, used_gas) = estimate_gas::estimate(
+        &recorder,
+        contract_id,
+        caller_id,
+        data.unwrap_or_default(),
+        value.unwrap_or_default(),
+        chain_id,
+        max_steps_to_execute,
+    )?;
+
+    let mut js = estimate_gas::estimation_json(&result, &exit_reason, steps_executed, used_gas);
+    js["access_list"] = recorder.access_list();
+
+    println!("{}", js);
+
+    Ok(())
+}
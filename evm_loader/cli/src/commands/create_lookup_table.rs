@@ -0,0 +1,61 @@
+use log::{debug, info};
+
+use solana_address_lookup_table_program::instruction::{create_lookup_table, extend_lookup_table};
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    transaction_signer::send_instructions,
+    Config,
+    NeonCliResult,
+};
+
+
+/// Addresses added by a single extend instruction to fit into a transaction
+const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+
+fn send(config: &Config, instruction: Instruction) -> NeonCliResult {
+    send_instructions(config, &[instruction], config.rpc_client.commitment())?;
+
+    Ok(())
+}
+
+/// Executes subcommand `create-lookup-table`.
+/// Creates an address lookup table owned by the signer, unless `lookup_table` is given,
+/// and extends it with `addresses` suggested by `emulate --lookup_table`.
+pub fn execute(
+    config: &Config,
+    lookup_table: Option<Pubkey>,
+    addresses: &[Pubkey],
+) -> NeonCliResult {
+    debug!("command_create_lookup_table(config={:?}, lookup_table={:?}, addresses={:?})", config, lookup_table, addresses);
+
+    let signer = config.signer.pubkey();
+
+    let lookup_table = match lookup_table {
+        Some(lookup_table) => lookup_table,
+        None => {
+            let recent_slot = config.rpc_client.get_slot_with_commitment(config.commitment)?;
+            let (instruction, lookup_table) = create_lookup_table(signer, signer, recent_slot);
+            send(config, instruction)?;
+            info!("lookup table {} created", lookup_table);
+            lookup_table
+        }
+    };
+
+    for chunk in addresses.chunks(MAX_ADDRESSES_PER_EXTEND) {
+        send(config, extend_lookup_table(lookup_table, signer, signer, chunk.to_vec()))?;
+        info!("lookup table {} extended with {} addresses", lookup_table, chunk.len());
+    }
+
+    println!("{}", serde_json::json!({
+        "lookup_table": lookup_table.to_string(),
+        "authority": signer.to_string(),
+        "added": addresses.len(),
+    }));
+
+    Ok(())
+}
@@ -18,7 +18,6 @@ use solana_sdk::{
     pubkey::Pubkey,
     transaction::Transaction,
     signers::Signers,
-    system_program,
     system_instruction,
     compute_budget::ComputeBudgetInstruction,
 };
@@ -52,7 +51,8 @@ use evm_loader::{
         COMPUTE_BUDGET_UNITS,
         COMPUTE_BUDGET_HEAP_FRAME,
         REQUEST_UNITS_ADDITIONAL_FEE,
-    }
+    },
+    instruction_builder::{self, OperatorAccounts},
 };
 
 use crate::{
@@ -68,7 +68,7 @@ fn get_ethereum_contract_account_credentials(
     config: &Config,
     caller_ether: &H160,
     trx_count: u64,
-) -> (Pubkey, H160, u8, Pubkey, String) {
+) -> (Pubkey, H160, Pubkey, String) {
     let creator = &config.signer;
 
     let (program_id, program_ether, program_nonce) = {
@@ -87,14 +87,13 @@ fn get_ethereum_contract_account_credentials(
     };
     debug!("Create code account: {}", &program_code.to_string());
 
-    (program_id, program_ether, program_nonce, program_code, program_seed)
+    (program_id, program_ether, program_code, program_seed)
 }
 
 fn create_ethereum_contract_accounts_in_solana(
     config: &Config,
     program_id: &Pubkey,
     program_ether: &H160,
-    program_nonce: u8,
     program_code: &Pubkey,
     program_seed: &str,
     program_code_len: usize,
@@ -121,15 +120,11 @@ fn create_ethereum_contract_accounts_in_solana(
             program_code_acc_len as u64,
             &config.evm_loader
         ),
-        Instruction::new_with_bincode(
-            config.evm_loader,
-            &(24_u8, program_ether.as_fixed_bytes(), program_nonce),
-            vec![
-                AccountMeta::new(creator.pubkey(), true),
-                AccountMeta::new_readonly(system_program::id(), false),
-                AccountMeta::new(*program_id, false),
-                AccountMeta::new(*program_code, false),
-            ]
+        instruction_builder::create_account(
+            &config.evm_loader,
+            &creator.pubkey(),
+            program_ether,
+            Some(program_code),
         )
     ];
 
@@ -151,12 +146,13 @@ fn fill_holder_account(
     for (chunk, i) in msg.chunks(DATA_CHUNK_SIZE).zip(0..) {
         let offset = u32::try_from(i*DATA_CHUNK_SIZE).unwrap();
 
-        let write_holder_instruction = Instruction::new_with_bincode(
-            config.evm_loader,
-            /* &EvmInstruction::WriteHolder {holder_id, offset, bytes: chunk}, */
-            &(0x12_u8, holder_id, offset, chunk),
-            vec![AccountMeta::new(*holder, false),
-                 AccountMeta::new(creator.pubkey(), true)]
+        let write_holder_instruction = instruction_builder::write_holder(
+            &config.evm_loader,
+            holder,
+            &creator.pubkey(),
+            holder_id,
+            offset,
+            chunk,
         );
 
         let instructions = vec![
@@ -394,6 +390,11 @@ pub fn execute(
     collateral_pool_base: &Pubkey,
     chain_id: u64
 ) -> NeonCliResult {
+    // Each step depends on the result of the previous transactions
+    if config.sign_only {
+        return Err(NeonCliError::SignOnlyNotSupported);
+    }
+
     let creator = &config.signer;
     let program_data = crate::read_program_data(program_location)?;
 
@@ -422,7 +423,7 @@ pub fn execute(
     // Get caller nonce
     let (trx_count, caller_ether) = crate::get_ether_account_nonce(config, &caller_sol)?;
 
-    let (program_id, program_ether, program_nonce, program_code, program_seed) =
+    let (program_id, program_ether, program_code, program_seed) =
         get_ethereum_contract_account_credentials(config, &caller_ether, trx_count);
 
     // Check program account to see if partial initialization has occurred
@@ -430,7 +431,6 @@ pub fn execute(
         config,
         &program_id,
         &program_ether,
-        program_nonce,
         &program_code,
         &program_seed,
         program_data.len(),
@@ -450,28 +450,30 @@ pub fn execute(
 
     let (collateral_pool_acc, collateral_pool_index) = crate::get_collateral_pool_account_and_index(config, collateral_pool_base);
 
-    let accounts = vec![
-        AccountMeta::new(storage, false),
-
-        AccountMeta::new(creator.pubkey(), true),
-        AccountMeta::new(collateral_pool_acc, false),
-        AccountMeta::new(caller_sol, false), // pay gas to yourself
-        AccountMeta::new_readonly(system_program::id(), false),
-        AccountMeta::new_readonly(config.evm_loader, false),
+    let operator = OperatorAccounts {
+        operator: creator.pubkey(),
+        treasury: collateral_pool_acc,
+        treasury_index: collateral_pool_index,
+        operator_ether_account: caller_sol, // pay gas to yourself
+    };
 
+    let accounts = vec![
         AccountMeta::new(program_id, false),
         AccountMeta::new(program_code, false),
         AccountMeta::new(caller_sol, false),
     ];
 
-    let mut holder_with_accounts = vec![AccountMeta::new(holder, false)];
-    holder_with_accounts.extend(accounts.clone());
     // Send trx_from_account_data_instruction
     {
-        debug!("trx_from_account_data_instruction holder_plus_accounts: {:?}", holder_with_accounts);
-        let trx_from_account_data_instruction = Instruction::new_with_bincode(config.evm_loader,
-                                                                              &(0x16_u8, collateral_pool_index, 0_u64),
-                                                                              holder_with_accounts);
+        let trx_from_account_data_instruction = instruction_builder::execute_trx_from_account_data_iterative(
+            &config.evm_loader,
+            &holder,
+            &storage,
+            &operator,
+            0,
+            &accounts,
+        );
+        debug!("trx_from_account_data_instruction: {:?}", trx_from_account_data_instruction);
         instructions.push(trx_from_account_data_instruction);
         debug!("instructions: {:?}", instructions);
         crate::send_transaction(config, &instructions)?;
@@ -479,13 +481,14 @@ pub fn execute(
 
     // Continue while no result
     loop {
-        let continue_accounts = accounts.clone();
-        debug!("continue continue_accounts: {:?}", continue_accounts);
-        let continue_instruction = Instruction::new_with_bincode(
-            config.evm_loader,
-            &(0x14_u8, collateral_pool_index, 400_u64),
-            continue_accounts
+        let continue_instruction = instruction_builder::continue_v02(
+            &config.evm_loader,
+            &storage,
+            &operator,
+            400,
+            &accounts,
         );
+        debug!("continue_instruction: {:?}", continue_instruction);
         let instructions = vec![
             ComputeBudgetInstruction::request_units(COMPUTE_BUDGET_UNITS, REQUEST_UNITS_ADDITIONAL_FEE),
             ComputeBudgetInstruction::request_heap_frame(COMPUTE_BUDGET_HEAP_FRAME),
@@ -1,27 +1,24 @@
-use log::{info, debug};
+use log::{info};
 
 use solana_sdk::{
     instruction::{AccountMeta, Instruction},
-    message::Message,
     pubkey::Pubkey,
-    transaction::Transaction,
-    system_program,
     compute_budget::ComputeBudgetInstruction,
 };
 
-use solana_cli::{
-    checks::{check_account_for_fee},
-};
-
 use evm::{H160};
 
-use evm_loader::config::{
-    COMPUTE_BUDGET_UNITS,
-    COMPUTE_BUDGET_HEAP_FRAME,
-    REQUEST_UNITS_ADDITIONAL_FEE,
+use evm_loader::{
+    config::{
+        COMPUTE_BUDGET_UNITS,
+        COMPUTE_BUDGET_HEAP_FRAME,
+        REQUEST_UNITS_ADDITIONAL_FEE,
+    },
+    instruction_builder,
 };
 
 use crate::{
+    transaction_signer::send_instructions,
     Config,
     NeonCliResult,
 };
@@ -42,11 +39,11 @@ pub fn execute(
     let ether_account = config.rpc_client.get_account(&ether_pubkey);
     if ether_account.is_err() {
         info!("No ether account for {}; will be created", ether_address);
-        instructions.push(create_ether_account_instruction(
-            config,
+        instructions.push(instruction_builder::create_account(
+            &config.evm_loader,
+            &config.signer.pubkey(),
             ether_address,
-            ether_pubkey,
-            nonce
+            None,
         ));
     }
 
@@ -54,7 +51,7 @@ pub fn execute(
 
     let signer_token_pubkey =
         spl_associated_token_account::get_associated_token_address(&config.signer.pubkey(), &token_mint_id);
-    let evm_token_authority = Pubkey::find_program_address(&[b"Deposit"], &config.evm_loader).0;
+    let evm_token_authority = instruction_builder::deposit_authority(&config.evm_loader);
 
     instructions.push(spl_approve_instruction(
         config,
@@ -63,33 +60,13 @@ pub fn execute(
         amount,
     ));
 
-    let evm_pool_pubkey =
-        spl_associated_token_account::get_associated_token_address(&evm_token_authority, &token_mint_id);
-
-    instructions.push(deposit_instruction(
-        config,
-        signer_token_pubkey,
-        evm_pool_pubkey,
-        ether_pubkey,
-        evm_token_authority,
+    instructions.push(instruction_builder::deposit(
+        &config.evm_loader,
+        &signer_token_pubkey,
+        &ether_pubkey,
     ));
 
-    let mut finalize_message = Message::new(&instructions, Some(&config.signer.pubkey()));
-    let blockhash = config.rpc_client.get_latest_blockhash()?;
-    finalize_message.recent_blockhash = blockhash;
-
-    check_account_for_fee(
-        &config.rpc_client,
-        &config.signer.pubkey(),
-        &finalize_message
-    )?;
-
-    let mut finalize_tx = Transaction::new_unsigned(finalize_message);
-
-    finalize_tx.try_sign(&[&*config.signer], blockhash)?;
-    debug!("signed: {:x?}", finalize_tx);
-
-    config.rpc_client.send_and_confirm_transaction_with_spinner(&finalize_tx)?;
+    send_instructions(config, &instructions, config.rpc_client.commitment())?;
 
     info!("{}", serde_json::json!({
         "amount": amount,
@@ -100,24 +77,6 @@ pub fn execute(
     Ok(())
 }
 
-/// Returns instruction for creation of account.
-fn create_ether_account_instruction(
-    config: &Config,
-    ether_address: &H160,
-    solana_address: Pubkey,
-    nonce: u8,
-) -> Instruction {
-    Instruction::new_with_bincode(
-        config.evm_loader,
-        &(24_u8, ether_address.as_fixed_bytes(), nonce),
-        vec![
-            AccountMeta::new(config.signer.pubkey(), true),
-            AccountMeta::new_readonly(system_program::id(), false),
-            AccountMeta::new(solana_address, false),
-        ]
-    )
-}
-
 /// Returns instruction to approve transfer of NEON tokens.
 fn spl_approve_instruction(
     config: &Config,
@@ -141,24 +100,3 @@ fn spl_approve_instruction(
         data,
     }
 }
-
-/// Returns instruction to deposit NEON tokens.
-fn deposit_instruction(
-    config: &Config,
-    source_pubkey: Pubkey,
-    destination_pubkey: Pubkey,
-    ether_account_pubkey: Pubkey,
-    evm_token_authority: Pubkey,
-) -> Instruction {
-    Instruction::new_with_bincode(
-        config.evm_loader,
-        &(25_u8),
-        vec![
-            AccountMeta::new(source_pubkey, false),
-            AccountMeta::new(destination_pubkey, false),
-            AccountMeta::new(ether_account_pubkey, false),
-            AccountMeta::new_readonly(evm_token_authority, false),
-            AccountMeta::new_readonly(spl_token::id(), false),
-        ],
-    )
-}
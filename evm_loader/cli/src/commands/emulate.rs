@@ -1,7 +1,11 @@
 use log::{debug, info};
 
 use evm::{H160, U256, ExitReason};
-use evm_loader::executor::Machine;
+use evm_loader::{
+    account_storage::AccountStorage,
+    executor::Machine,
+    transaction::{UnsignedTransaction, verify_tx_signature},
+};
 
 use crate::{
     account_storage::{
@@ -9,11 +13,110 @@ use crate::{
     },
     Config,
     NeonCliResult,
+    profiler::Profiler,
+    session::Session,
     syscall_stubs::Stubs,
 };
 
-use solana_sdk::pubkey::Pubkey;
-use crate::{errors};
+use solana_sdk::{
+    packet::PACKET_DATA_SIZE,
+    pubkey::{Pubkey, PUBKEY_BYTES},
+};
+use std::{collections::BTreeMap, io::{Error, ErrorKind}, path::Path, time::Instant};
+use crate::{errors, revert::{self, ErrorAbi}};
+
+/// Serialized size of a legacy iterative transaction without emulated accounts:
+/// - operator signature with the length prefix, 1 + 64
+/// - message header, 3
+/// - 8 static keys with the length prefix: operator, treasury, operator Ethereum account,
+///   system program, evm_loader, holder, storage, compute budget program, 1 + 8 * 32
+/// - recent blockhash, 32
+/// - instructions length prefix, 1
+/// - compute budget request units and request heap frame, (3 + 9) + (3 + 5)
+/// - iterative instruction with 7 accounts and 13 bytes of data, 1 + (1 + 7) + (1 + 13)
+const ITERATIVE_TRANSACTION_SIZE: usize =
+    (1 + 64) + 3 + (1 + 8 * PUBKEY_BYTES) + 32 + 1 + (3 + 9) + (3 + 5) + (1 + (1 + 7) + (1 + 13));
+
+/// Emulated accounts fitting into a legacy transaction
+/// along with the accounts of the iterative instruction.
+/// Each one adds a static key and an instruction account index.
+const LEGACY_TRANSACTION_ACCOUNTS: usize = (PACKET_DATA_SIZE - ITERATIVE_TRANSACTION_SIZE) / (PUBKEY_BYTES + 1);
+
+/// Accounts that fit into a legacy transaction stay static keys,
+/// the rest are suggested for an address lookup table, so the table is as small as possible.
+/// Writable accounts are kept static first.
+fn lookup_table_partition(accounts: &BTreeMap<Pubkey, bool>) -> serde_json::Value {
+    let mut keys: Vec<(&Pubkey, &bool)> = accounts.iter().collect();
+    keys.sort_by_key(|&(_, &is_writable)| !is_writable);
+
+    let split = keys.len().min(LEGACY_TRANSACTION_ACCOUNTS);
+    let (static_keys, addresses) = keys.split_at(split);
+
+    let to_json = |keys: &[(&Pubkey, &bool)]| keys.iter()
+        .map(|(pubkey, is_writable)| serde_json::json!({
+            "pubkey": pubkey.to_string(),
+            "is_writable": is_writable,
+        }))
+        .collect::<Vec<_>>();
+
+    serde_json::json!({
+        "static_keys": to_json(static_keys),
+        "addresses": to_json(addresses),
+    })
+}
+
+fn invalid_transaction(message: String) -> errors::NeonCliError {
+    errors::NeonCliError::StdIoError(Error::new(ErrorKind::InvalidData, message))
+}
+
+/// Decodes raw signed legacy (optionally EIP-155) transaction the same way as the program does.
+/// Returns the recovered sender and the transaction.
+pub fn decode_raw_transaction(raw: &[u8], chain_id: u64) -> Result<(H160, UnsignedTransaction), errors::NeonCliError> {
+    let decoder_error = |e: rlp::DecoderError| invalid_transaction(format!("RLP DecoderError={}", e));
+
+    let rlp = rlp::Rlp::new(raw);
+    if rlp.item_count().map_err(decoder_error)? != 9 {
+        return Err(invalid_transaction("signed transaction must have 9 fields".to_string()));
+    }
+
+    let v: u64 = rlp.val_at(6).map_err(decoder_error)?;
+    let r: U256 = rlp.val_at(7).map_err(decoder_error)?;
+    let s: U256 = rlp.val_at(8).map_err(decoder_error)?;
+
+    let (signed_chain_id, recovery_id) = match v {
+        27 | 28 => (None, v - 27),
+        v if v >= 35 => (Some((v - 35) / 2), (v - 35) % 2),
+        v => return Err(invalid_transaction(format!("invalid signature v={}", v))),
+    };
+
+    // Signed message is the transaction without signature, with chain_id for EIP-155
+    let mut stream = rlp::RlpStream::new_list(if signed_chain_id.is_some() { 9 } else { 6 });
+    for i in 0..6 {
+        stream.append_raw(rlp.at(i).map_err(decoder_error)?.as_raw(), 1);
+    }
+    if let Some(signed_chain_id) = signed_chain_id {
+        stream.append(&signed_chain_id);
+        stream.append(&0_u8);
+        stream.append(&0_u8);
+    }
+    let unsigned_trx = stream.out();
+
+    let mut signature = [0_u8; 65];
+    r.to_big_endian(&mut signature[0..32]);
+    s.to_big_endian(&mut signature[32..64]);
+    signature[64] = u8::from(recovery_id == 1);
+
+    let sender = verify_tx_signature(&signature, &unsigned_trx)?;
+    let transaction = UnsignedTransaction::from_rlp(&unsigned_trx)?;
+
+    if let Some(trx_chain_id) = transaction.chain_id {
+        if trx_chain_id != U256::from(chain_id) {
+            return Err(invalid_transaction(format!("invalid chain_id: actual {}, expected {}", trx_chain_id, chain_id)));
+        }
+    }
+
+    Ok((sender, transaction))
+}
 
 #[allow(clippy::too_many_arguments, clippy::too_many_lines)]
 pub fn execute(
@@ -22,36 +125,70 @@ pub fn execute(
     caller_id: H160, 
     data: Option<Vec<u8>>,
     value: Option<U256>,
+    gas_limit: Option<U256>,
     token_mint: &Pubkey,
     chain_id: u64,
     max_steps_to_execute: u64,
+    read_only: bool,
+    errors: &[ErrorAbi],
+    session_path: Option<&Path>,
+    profile: bool,
+    lookup_table: bool,
 ) -> NeonCliResult {
-    debug!("command_emulate(config={:?}, contract_id={:?}, caller_id={:?}, data={:?}, value={:?})",
+    debug!("command_emulate(config={:?}, contract_id={:?}, caller_id={:?}, data={:?}, value={:?}, gas_limit={:?}, read_only={}, session={:?}, profile={}, lookup_table={})",
         config,
         contract_id,
         caller_id,
         &hex::encode(data.clone().unwrap_or_default()),
-        value);
+        value,
+        gas_limit,
+        read_only,
+        session_path,
+        profile,
+        lookup_table);
+
+    // Deployment always goes through the full path
+    let read_only = read_only && contract_id.is_some();
 
-    let syscall_stubs = Stubs::new(config)?;
+    // Stage durations are plain log lines, there are no tracing spans or exporter to collect them
+    let setup_begin = Instant::now();
+    let syscall_stubs = if read_only {
+        Stubs::with_default_rent()
+    } else {
+        Stubs::new(config)?
+    };
     solana_sdk::program_stubs::set_syscall_stubs(syscall_stubs);
 
-    let storage = EmulatorAccountStorage::new(config, *token_mint, chain_id);
+    let mut session = session_path.map(Session::load).transpose()?;
+
+    let mut storage = EmulatorAccountStorage::new(config, *token_mint, chain_id);
+    if let Some(session) = &session {
+        storage = storage.with_session(session);
+    }
 
     let program_id = if let Some(program_id) = contract_id {
         debug!("program_id to call: {}", program_id);
         program_id
     } else {
-        let (solana_address, _nonce) = crate::make_solana_program_address(&caller_id, &config.evm_loader);
-        let trx_count = crate::get_ether_account_nonce(config, &solana_address)?;
-        let trx_count= trx_count.0;
+        let trx_count = if session.is_some() {
+            storage.nonce(&caller_id).as_u64()
+        } else {
+            let (solana_address, _nonce) = crate::make_solana_program_address(&caller_id, &config.evm_loader);
+            let trx_count = crate::get_ether_account_nonce(config, &solana_address)?;
+            trx_count.0
+        };
         let program_id = crate::get_program_ether(&caller_id, trx_count);
         debug!("program_id to deploy: {}", program_id);
         program_id
     };
 
+    info!("Stage setup took {:?}", setup_begin.elapsed());
+
+    let mut profiler = if profile { Some(Profiler::default()) } else { None };
+
+    let execution_begin = Instant::now();
     let (exit_reason, result, actions, steps_executed, used_gas) = {
-        let gas_limit = U256::from(999_999_999_999_u64);
+        let gas_limit = gas_limit.unwrap_or_else(|| U256::from(999_999_999_999_u64));
         let mut executor = Machine::new(caller_id, &storage)?;
         debug!("Executor initialized");
 
@@ -63,12 +200,24 @@ pub fn execute(
                     &hex::encode(data.clone().unwrap_or_default()),
                     value);
 
-                executor.call_begin(caller_id,
-                    program_id,
-                    data.unwrap_or_default(),
-                    value.unwrap_or_default(),
-                    gas_limit, U256::zero())?;
-                match executor.execute_n_steps(max_steps_to_execute){
+                if read_only {
+                    executor.call_begin_read_only(caller_id,
+                        program_id,
+                        data.unwrap_or_default(),
+                        value.unwrap_or_default(),
+                        gas_limit);
+                } else {
+                    executor.call_begin(caller_id,
+                        program_id,
+                        data.unwrap_or_default(),
+                        value.unwrap_or_default(),
+                        gas_limit, U256::zero())?;
+                }
+                let executed = match &mut profiler {
+                    Some(profiler) => profiler.execute_n_steps(&mut executor, max_steps_to_execute),
+                    None => executor.execute_n_steps(max_steps_to_execute),
+                };
+                match executed {
                     Ok(()) => {
                         info!("too many steps");
                         return Err(errors::NeonCliError::TooManySteps)
@@ -85,7 +234,11 @@ pub fn execute(
                     data.unwrap_or_default(),
                     value.unwrap_or_default(),
                     gas_limit, U256::zero())?;
-                match executor.execute_n_steps(max_steps_to_execute){
+                let executed = match &mut profiler {
+                    Some(profiler) => profiler.execute_n_steps(&mut executor, max_steps_to_execute),
+                    None => executor.execute_n_steps(max_steps_to_execute),
+                };
+                match executed {
                     Ok(()) => {
                         info!("too many steps");
                         return Err(errors::NeonCliError::TooManySteps)
@@ -110,9 +263,19 @@ pub fn execute(
     };
 
     debug!("Call done");
+    info!("Stage execution took {:?}", execution_begin.elapsed());
+    crate::metrics::inc(&crate::metrics::EMULATIONS);
+    crate::metrics::add(&crate::metrics::STEPS_EXECUTED, steps_executed);
+
+    let output_begin = Instant::now();
     let status = match exit_reason {
+        ExitReason::Succeed(_) if read_only => "succeed".to_string(),
         ExitReason::Succeed(_) => {
-            storage.apply_actions(actions.unwrap());
+            let actions = actions.unwrap();
+            if let Some(session) = &mut session {
+                session.apply_actions(&storage, &actions);
+            }
+            storage.apply_actions(actions);
 
             debug!("Applies done");
             "succeed".to_string()
@@ -142,7 +305,7 @@ pub fn execute(
         .cloned()
         .collect();
 
-    let js = serde_json::json!({
+    let mut js = serde_json::json!({
         "accounts": accounts,
         "solana_accounts": solana_accounts,
         "token_accounts": [],
@@ -153,8 +316,110 @@ pub fn execute(
         "used_gas": used_gas.as_u64(),
     });
 
+    if let ExitReason::Revert(_) = exit_reason {
+        if let Some(reason) = revert::decode_revert_reason(&result) {
+            info!("revert reason: {}", reason);
+            js["revert_reason"] = reason.into();
+        }
+        if let Some(code) = revert::decode_panic_code(&result) {
+            info!("panic code: {:#x}", code);
+            js["panic_code"] = format!("{:#x}", code).into();
+        }
+        if let Some(error) = revert::decode_custom_error(&result, errors) {
+            info!("custom error: {}", error);
+            js["custom_error"] = error;
+        }
+    }
+
+    if let Some(profiler) = &profiler {
+        js["profile"] = profiler.to_json();
+    }
+
+    if lookup_table {
+        js["lookup_table"] = lookup_table_partition(&storage.used_solana_accounts());
+    }
+
+    if let (Some(session), Some(path)) = (&session, session_path) {
+        session.save(path)?;
+    }
+
     println!("{}", js);
+    info!("Stage output took {:?}", output_begin.elapsed());
 
     Ok(())
 }
 
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use evm_loader::{
+        config::{COMPUTE_BUDGET_HEAP_FRAME, COMPUTE_BUDGET_UNITS, REQUEST_UNITS_ADDITIONAL_FEE},
+        instruction_builder::{self, OperatorAccounts},
+    };
+    use solana_sdk::{
+        compute_budget::ComputeBudgetInstruction,
+        instruction::AccountMeta,
+        packet::PACKET_DATA_SIZE,
+        pubkey::Pubkey,
+        transaction::Transaction,
+    };
+
+    use super::{lookup_table_partition, LEGACY_TRANSACTION_ACCOUNTS};
+
+    fn iterative_transaction_size(accounts: usize) -> usize {
+        let program_id = Pubkey::new_unique();
+        let operator = OperatorAccounts {
+            operator: Pubkey::new_unique(),
+            treasury: Pubkey::new_unique(),
+            treasury_index: 0,
+            operator_ether_account: Pubkey::new_unique(),
+        };
+        let accounts: Vec<AccountMeta> = (0..accounts)
+            .map(|_| AccountMeta::new(Pubkey::new_unique(), false))
+            .collect();
+
+        let instructions = [
+            ComputeBudgetInstruction::request_units(COMPUTE_BUDGET_UNITS, REQUEST_UNITS_ADDITIONAL_FEE),
+            ComputeBudgetInstruction::request_heap_frame(COMPUTE_BUDGET_HEAP_FRAME),
+            instruction_builder::execute_trx_from_account_data_iterative(
+                &program_id,
+                &Pubkey::new_unique(),
+                &Pubkey::new_unique(),
+                &operator,
+                0,
+                &accounts,
+            ),
+        ];
+        let transaction = Transaction::new_with_payer(&instructions, Some(&operator.operator));
+
+        bincode::serialize(&transaction).unwrap().len()
+    }
+
+    #[test]
+    fn test_legacy_transaction_accounts() {
+        assert_eq!(LEGACY_TRANSACTION_ACCOUNTS, 25);
+
+        assert!(iterative_transaction_size(LEGACY_TRANSACTION_ACCOUNTS) <= PACKET_DATA_SIZE);
+        assert!(iterative_transaction_size(LEGACY_TRANSACTION_ACCOUNTS + 1) > PACKET_DATA_SIZE);
+    }
+
+    #[test]
+    fn test_lookup_table_partition() {
+        let partition_sizes = |accounts: usize| {
+            let accounts: BTreeMap<Pubkey, bool> = (0..accounts)
+                .map(|i| (Pubkey::new_unique(), i % 2 == 0))
+                .collect();
+            let partition = lookup_table_partition(&accounts);
+
+            (
+                partition["static_keys"].as_array().unwrap().len(),
+                partition["addresses"].as_array().unwrap().len(),
+            )
+        };
+
+        assert_eq!(partition_sizes(LEGACY_TRANSACTION_ACCOUNTS), (LEGACY_TRANSACTION_ACCOUNTS, 0));
+        assert_eq!(partition_sizes(LEGACY_TRANSACTION_ACCOUNTS + 1), (LEGACY_TRANSACTION_ACCOUNTS, 1));
+    }
+}
@@ -0,0 +1,103 @@
+use std::io::{Error, ErrorKind};
+
+use log::{debug, info};
+
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+
+use evm_loader::transaction::{UnsignedTransaction, verify_tx_signature};
+
+use crate::{
+    commands::{
+        emulate,
+        get_holder::{holder_transaction, signed_transaction_hash},
+        get_neon_trx_timeline::{
+            self,
+            get_transaction,
+            instruction_transaction,
+            restore_holder,
+        },
+    },
+    errors::NeonCliError,
+    revert::ErrorAbi,
+    Config,
+    NeonCliResult,
+};
+
+
+/// Signature and unsigned transaction executed by the Solana transaction.
+/// Holder content is restored by replaying holder writes made before the transaction.
+fn neon_transaction(config: &Config, signature: &Signature, limit: usize) -> Result<([u8; 65], Vec<u8>), NeonCliError> {
+    let transaction = get_transaction(config, signature)?;
+
+    for instruction in &transaction.instructions {
+        let signed = match instruction.tag {
+            get_neon_trx_timeline::TAG_EXECUTE_FROM_INSTRUCTION => {
+                instruction_transaction(&instruction.data, get_neon_trx_timeline::EXECUTE_FROM_INSTRUCTION_PREFIX_LEN)
+                    .map(|(signature, trx)| (signature, trx.to_vec()))
+            },
+            get_neon_trx_timeline::TAG_BEGIN_FROM_INSTRUCTION | get_neon_trx_timeline::TAG_STEP_FROM_INSTRUCTION => {
+                instruction_transaction(&instruction.data, get_neon_trx_timeline::STEP_FROM_INSTRUCTION_PREFIX_LEN)
+                    .map(|(signature, trx)| (signature, trx.to_vec()))
+            },
+            get_neon_trx_timeline::TAG_BEGIN_FROM_ACCOUNT
+            | get_neon_trx_timeline::TAG_STEP_FROM_ACCOUNT
+            | get_neon_trx_timeline::TAG_STEP_FROM_ACCOUNT_NO_CHAINID => {
+                let holder: &Pubkey = match instruction.accounts.first() {
+                    Some(holder) => holder,
+                    None => continue,
+                };
+                info!("restore holder {} before {}", holder, signature);
+
+                let (data, _) = restore_holder(config, holder, *signature, limit)?;
+                holder_transaction(&data).map(|(signature, trx)| (signature, trx.to_vec()))
+            },
+            _ => None,
+        };
+
+        if let Some(signed) = signed {
+            return Ok(signed);
+        }
+    }
+
+    Err(Error::new(
+        ErrorKind::NotFound,
+        format!("{}: no Neon transaction in instructions, continue and cancel refer to the state account", signature)
+    ).into())
+}
+
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    config: &Config,
+    signature: &Signature,
+    token_mint: &Pubkey,
+    chain_id: u64,
+    max_steps_to_execute: u64,
+    errors: &[ErrorAbi],
+    limit: usize,
+    profile: bool,
+) -> NeonCliResult {
+    debug!("command_emulate_solana_trx(config={:?}, signature={}, limit={})", config, signature, limit);
+
+    let (trx_signature, unsigned_trx) = neon_transaction(config, signature, limit)?;
+    let sender = verify_tx_signature(&trx_signature, &unsigned_trx)?;
+    let trx = UnsignedTransaction::from_rlp(&unsigned_trx)?;
+
+    info!("Neon transaction 0x{} from {}", hex::encode(signed_transaction_hash(&trx, &trx_signature)), sender);
+
+    // Historical state is not available, the transaction is emulated against the current one
+    emulate::execute(config,
+                     trx.to,
+                     sender,
+                     Some(trx.call_data),
+                     Some(trx.value),
+                     Some(trx.gas_limit),
+                     token_mint,
+                     chain_id,
+                     max_steps_to_execute,
+                     false,
+                     errors,
+                     None,
+                     profile,
+                     false)
+}
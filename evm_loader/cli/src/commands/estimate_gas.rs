@@ -0,0 +1,165 @@
+use log::{debug, info};
+
+use evm::{H160, U256, ExitReason};
+use evm_loader::{
+    account_storage::AccountStorage,
+    executor::Machine,
+    transaction::UnsignedTransaction,
+};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::{
+    account_storage::EmulatorAccountStorage,
+    errors::NeonCliError,
+    revert,
+    syscall_stubs::Stubs,
+    Config,
+    NeonCliResult,
+};
+
+
+/// Transaction with the largest possible gas price and gas limit,
+/// its size is not less than the size of the transaction the client signs
+fn transaction_upper_bound(
+    nonce: u64,
+    to: Option<H160>,
+    value: U256,
+    call_data: Vec<u8>,
+    chain_id: u64,
+) -> UnsignedTransaction {
+    let mut trx = UnsignedTransaction {
+        nonce,
+        gas_price: U256::from(u64::MAX),
+        gas_limit: U256::from(u64::MAX),
+        to,
+        value,
+        call_data,
+        chain_id: Some(U256::from(chain_id)),
+        rlp_len: 0,
+    };
+
+    let mut stream = rlp::RlpStream::new_list(9);
+    stream.append(&trx.nonce);
+    stream.append(&trx.gas_price);
+    stream.append(&trx.gas_limit);
+    match trx.to.as_ref() {
+        None => stream.append(&""),
+        Some(address) => stream.append(address),
+    };
+    stream.append(&trx.value);
+    stream.append(&trx.call_data);
+    stream.append(&trx.chain_id.unwrap_or_default());
+    stream.append(&0_u8);
+    stream.append(&0_u8);
+
+    trx.rlp_len = stream.out().len();
+    trx
+}
+
+
+/// Emulates the transaction and charges the overhead of the iterative execution
+/// from a holder account, the most expensive way the operator can execute it.
+/// Rent of created accounts and storage cells is charged by the emulation itself.
+/// Returns the result, the exit reason, executed steps and used gas.
+pub fn estimate<B: AccountStorage>(
+    storage: &B,
+    contract_id: Option<H160>,
+    caller_id: H160,
+    data: Vec<u8>,
+    value: U256,
+    chain_id: u64,
+    max_steps_to_execute: u64,
+) -> Result<(Vec<u8>, ExitReason, u64, U256), NeonCliError> {
+    let nonce = storage.nonce(&caller_id).as_u64();
+    let trx = transaction_upper_bound(nonce, contract_id, value, data.clone(), chain_id);
+    let gas_limit = U256::from(999_999_999_999_u64);
+
+    let mut executor = Machine::new(caller_id, storage)?;
+    executor.gasometer_mut().record_iterative_overhead();
+    executor.gasometer_mut().record_transaction_size(&trx);
+
+    match contract_id {
+        Some(contract_id) => executor.call_begin(caller_id, contract_id, data, value, gas_limit, U256::zero())?,
+        None => executor.create_begin(caller_id, data, value, gas_limit, U256::zero())?,
+    }
+
+    let (result, exit_reason) = match executor.execute_n_steps(max_steps_to_execute) {
+        Ok(()) => {
+            info!("too many steps");
+            return Err(NeonCliError::TooManySteps);
+        },
+        Err(result) => result,
+    };
+
+    let steps_executed = executor.get_steps_executed();
+    executor.gasometer_mut().pad_evm_steps(steps_executed);
+    let used_gas = executor.used_gas();
+    info!("estimate_gas: exit_reason={:?}, steps_executed={}, used_gas={}", exit_reason, steps_executed, used_gas);
+
+    Ok((result, exit_reason, steps_executed, used_gas))
+}
+
+#[must_use]
+pub fn estimation_json(result: &[u8], exit_reason: &ExitReason, steps_executed: u64, used_gas: U256) -> serde_json::Value {
+    let status = match exit_reason {
+        ExitReason::Succeed(_) => "succeed",
+        ExitReason::Error(_) => "error",
+        ExitReason::Revert(_) => "revert",
+        ExitReason::Fatal(_) => "fatal",
+        ExitReason::StepLimitReached => unreachable!(),
+    };
+
+    let mut js = serde_json::json!({
+        "result": &hex::encode(result),
+        "exit_status": status,
+        "exit_reason": exit_reason,
+        "steps_executed": steps_executed,
+        "gas_estimate": used_gas.as_u64(),
+    });
+
+    if let ExitReason::Revert(_) = exit_reason {
+        if let Some(reason) = revert::decode_revert_reason(result) {
+            js["revert_reason"] = reason.into();
+        }
+    }
+
+    js
+}
+
+
+#[allow(clippy::too_many_arguments)]
+pub fn execute(
+    config: &Config,
+    contract_id: Option<H160>,
+    caller_id: H160,
+    data: Option<Vec<u8>>,
+    value: Option<U256>,
+    token_mint: &Pubkey,
+    chain_id: u64,
+    max_steps_to_execute: u64,
+) -> NeonCliResult {
+    debug!("command_estimate_gas(config={:?}, contract_id={:?}, caller_id={:?}, data={:?}, value={:?})",
+        config,
+        contract_id,
+        caller_id,
+        &hex::encode(data.clone().unwrap_or_default()),
+        value);
+
+    solana_sdk::program_stubs::set_syscall_stubs(Stubs::new(config)?);
+
+    let storage = EmulatorAccountStorage::new(config, *token_mint, chain_id);
+
+    let (result, exit_reason, steps_executed, used_gas) = estimate(
+        &storage,
+        contract_id,
+        caller_id,
+        data.unwrap_or_default(),
+        value.unwrap_or_default(),
+        chain_id,
+        max_steps_to_execute,
+    )?;
+
+    println!("{}", estimation_json(&result, &exit_reason, steps_executed, used_gas));
+
+    Ok(())
+}
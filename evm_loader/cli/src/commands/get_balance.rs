@@ -0,0 +1,72 @@
+use log::debug;
+
+use solana_sdk::pubkey::Pubkey;
+
+use evm::{H160, U256};
+
+use evm_loader::account::EthereumAccount;
+
+use crate::{
+    account_storage::{make_solana_program_address, account_info, get_multiple_accounts},
+    errors::NeonCliError,
+    Config,
+    NeonCliResult,
+};
+
+
+/// Balance and nonce of the Ethereum account, zero if the account doesn't exist
+pub struct Balance {
+    pub address: H160,
+    pub solana_address: Pubkey,
+    pub balance: U256,
+    pub trx_count: u64,
+}
+
+/// Reads balances of several accounts.
+/// All account addresses are derived first and fetched with `getMultipleAccounts`.
+pub fn read_balances(config: &Config, addresses: &[H160]) -> Result<Vec<Balance>, NeonCliError> {
+    let keys: Vec<Pubkey> = addresses.iter()
+        .map(|address| make_solana_program_address(address, &config.evm_loader).0)
+        .collect();
+
+    let mut accounts = get_multiple_accounts(config, &keys)?;
+
+    let balances = addresses.iter().zip(keys)
+        .map(|(address, key)| {
+            let (balance, trx_count) = accounts.get_mut(&key)
+                .and_then(|account| {
+                    let info = account_info(&key, account);
+                    EthereumAccount::from_account(&config.evm_loader, &info).ok()
+                        .map(|account| (account.balance, account.trx_count))
+                })
+                .unwrap_or_default();
+
+            Balance { address: *address, solana_address: key, balance, trx_count }
+        })
+        .collect();
+
+    Ok(balances)
+}
+
+
+pub fn execute(
+    config: &Config,
+    addresses: &[H160],
+) -> NeonCliResult {
+    debug!("command_get_balance(config={:?}, addresses={:?})", config, addresses);
+
+    let balances = read_balances(config, addresses)?;
+
+    let js: Vec<serde_json::Value> = balances.iter()
+        .map(|balance| serde_json::json!({
+            "address": format!("0x{}", hex::encode(balance.address.as_bytes())),
+            "solana_address": balance.solana_address.to_string(),
+            "balance": format!("{:#x}", balance.balance),
+            "trx_count": balance.trx_count,
+        }))
+        .collect();
+
+    println!("{}", serde_json::Value::Array(js));
+
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use log::{debug, info};
+
+use evm::{H160, H256};
+
+use evm_loader::account::{EthereumAccount, EthereumContract};
+
+use crate::{
+    account_storage::{EmulatorAccountStorage, account_info},
+    Config,
+    NeonCliResult,
+};
+
+
+/// EIP-1014 contract address: keccak256(0xff ++ deployer ++ salt ++ keccak256(init_code))[12..]
+#[must_use]
+pub fn create2_address(deployer: &H160, salt: &H256, init_code_hash: &H256) -> H160 {
+    let mut buffer = Vec::with_capacity(1 + 20 + 32 + 32);
+    buffer.push(0xff_u8);
+    buffer.extend_from_slice(deployer.as_bytes());
+    buffer.extend_from_slice(salt.as_bytes());
+    buffer.extend_from_slice(init_code_hash.as_bytes());
+
+    let hash = crate::keccak256_h256(&buffer);
+    H160::from_slice(&hash[12..32])
+}
+
+
+pub fn execute(
+    config: &Config,
+    deployer: &H160,
+    salt: &H256,
+    init_code_hash: &H256,
+) -> NeonCliResult {
+    debug!("command_get_create2_address(config={:?}, deployer={}, salt={:?}, init_code_hash={:?})",
+        config, deployer, salt, init_code_hash);
+
+    let address = create2_address(deployer, salt, init_code_hash);
+    let (solana_address, bump_seed) = crate::make_solana_program_address(&address, &config.evm_loader);
+    info!("create2 address 0x{} => {}", hex::encode(address.as_bytes()), solana_address);
+
+    let mut js = serde_json::json!({
+        "address": format!("0x{}", hex::encode(address.as_bytes())),
+        "solana_address": solana_address.to_string(),
+        "bump_seed": bump_seed,
+        "exists": false,
+        "collision": false,
+    });
+
+    if let Some((mut account, code_account)) = EmulatorAccountStorage::get_account_from_solana(config, &address) {
+        let info = account_info(&solana_address, &mut account);
+        let ether_account = EthereumAccount::from_account(&config.evm_loader, &info)?;
+
+        js["exists"] = true.into();
+        js["trx_count"] = ether_account.trx_count.into();
+        js["balance"] = ether_account.balance.to_string().into();
+
+        let mut code_size = 0_u32;
+        if let (Some(code_address), Some(mut code_account)) = (ether_account.code_account, code_account) {
+            let code_info = account_info(&code_address, &mut code_account);
+            let contract = EthereumContract::from_account(&config.evm_loader, &code_info)?;
+            code_size = contract.code_size;
+
+            js["code_account"] = code_address.to_string().into();
+            js["code_size"] = contract.code_size.into();
+            // Non-zero generation without code means the contract was destroyed by SELFDESTRUCT
+            js["generation"] = contract.generation.into();
+            js["suicided"] = (contract.generation > 0 && contract.code_size == 0).into();
+        }
+
+        // EIP-684: creation fails if the target has a nonce or code
+        js["collision"] = (ether_account.trx_count != 0 || code_size != 0).into();
+    }
+
+    println!("{}", js);
+
+    Ok(())
+}
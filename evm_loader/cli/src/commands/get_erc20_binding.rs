@@ -0,0 +1,132 @@
+use log::{debug, info};
+
+use evm::{H160, U256};
+
+use solana_sdk::{
+    program_option::COption,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+
+use evm_loader::{
+    account::{ACCOUNT_SEED_VERSION, EthereumAccount, EthereumContract},
+    executor::Machine,
+};
+
+use crate::{
+    account_storage::{EmulatorAccountStorage, account_info},
+    commands::get_neon_elf::token_mint_and_chain_id,
+    errors::NeonCliError,
+    syscall_stubs::Stubs,
+    Config,
+    NeonCliResult,
+};
+
+// ERC20 wrapper system contract address, see evm_loader::precompile
+const ERC20_WRAPPER_ADDRESS: &str = "ff00000000000000000000000000000000000001";
+
+// [0xaf, 0x25, 0xc0, 0x77] : "tokenMint()" of ERC20ForSpl
+const TOKEN_MINT_METHOD_ID: [u8; 4] = [0xaf, 0x25, 0xc0, 0x77];
+
+/// Enough for a storage or immutable getter
+const TOKEN_MINT_MAX_STEPS: u64 = 10_000;
+
+
+fn coption_to_string(value: COption<Pubkey>) -> Option<String> {
+    match value {
+        COption::Some(key) => Some(key.to_string()),
+        COption::None => None,
+    }
+}
+
+/// Token mint returned by the `tokenMint()` getter of the contract, read-only emulation.
+/// `None` if the contract does not implement the getter.
+fn wrapped_token_mint(config: &Config, contract: &H160) -> Result<Option<Pubkey>, NeonCliError> {
+    solana_sdk::program_stubs::set_syscall_stubs(Stubs::with_default_rent());
+
+    let (neon_token_mint, chain_id) = token_mint_and_chain_id(config, None, None);
+    let storage = EmulatorAccountStorage::new(config, neon_token_mint, chain_id);
+    let mut machine = Machine::new(H160::zero(), &storage)?;
+    machine.call_begin_read_only(H160::zero(), *contract, TOKEN_MINT_METHOD_ID.to_vec(), U256::zero(), U256::from(999_999_999_999_u64));
+
+    let (result, exit_reason) = match machine.execute_n_steps(TOKEN_MINT_MAX_STEPS) {
+        Ok(()) => return Err(NeonCliError::TooManySteps),
+        Err(result) => result,
+    };
+    debug!("tokenMint() of {}: exit_reason={:?}, result={}", contract, exit_reason, hex::encode(&result));
+
+    if !exit_reason.is_succeed() || result.len() != 32 {
+        return Ok(None);
+    }
+
+    Ok(Some(Pubkey::new(&result)))
+}
+
+
+pub fn execute(
+    config: &Config,
+    token_mint: &Pubkey,
+    contract: &H160,
+) -> NeonCliResult {
+    debug!("command_get_erc20_binding(config={:?}, token_mint={}, contract={})", config, token_mint, contract);
+
+    let mint_account = config.rpc_client.get_account_with_commitment(token_mint, config.commitment)?
+        .value
+        .ok_or(NeonCliError::AccountNotFound(*token_mint))?;
+    if !spl_token::check_id(&mint_account.owner) {
+        return Err(NeonCliError::InvalidTokenMint(*token_mint));
+    }
+
+    let mint = spl_token::state::Mint::unpack(&mint_account.data)
+        .map_err(|_| NeonCliError::InvalidTokenMint(*token_mint))?;
+    info!("token mint {}: decimals {}, supply {}", token_mint, mint.decimals, mint.supply);
+
+    let (mut account, code_account) = EmulatorAccountStorage::get_account_from_solana(config, contract)
+        .ok_or(NeonCliError::AccountNotFoundAtAddress(*contract))?;
+    let mut code_account = code_account
+        .ok_or(NeonCliError::CodeAccountNotFound(*contract))?;
+
+    let (solana_address, _) = crate::make_solana_program_address(contract, &config.evm_loader);
+    let code_address = {
+        let info = account_info(&solana_address, &mut account);
+        let ether_account = EthereumAccount::from_account(&config.evm_loader, &info)?;
+        ether_account.code_account.ok_or(NeonCliError::CodeAccountNotFound(*contract))?
+    };
+
+    let code_info = account_info(&code_address, &mut code_account);
+    let code_data = EthereumContract::from_account(&config.evm_loader, &code_info)?;
+    if code_data.code_size == 0 {
+        return Err(NeonCliError::CodeAccountRequired(*contract));
+    }
+
+    if wrapped_token_mint(config, contract)? != Some(*token_mint) {
+        return Err(NeonCliError::TokenMintMismatch(*contract, *token_mint));
+    }
+
+    // The owner's Ethereum address is the last seed of a balance account
+    let balance_seed_prefix = serde_json::json!([
+        hex::encode(&[ACCOUNT_SEED_VERSION]),
+        hex::encode(b"ERC20Balance"),
+        hex::encode(token_mint.to_bytes()),
+        hex::encode(contract.as_bytes()),
+    ]);
+
+    let js = serde_json::json!({
+        "token_mint": token_mint.to_string(),
+        "token_program": spl_token::id().to_string(),
+        "decimals": mint.decimals,
+        "supply": mint.supply,
+        "mint_authority": coption_to_string(mint.mint_authority),
+        "freeze_authority": coption_to_string(mint.freeze_authority),
+        "contract": format!("0x{}", hex::encode(contract.as_bytes())),
+        "contract_solana_address": solana_address.to_string(),
+        "contract_code_account": code_address.to_string(),
+        "erc20_wrapper": format!("0x{}", ERC20_WRAPPER_ADDRESS),
+        "evm_loader": config.evm_loader.to_string(),
+        "balance_account_seed_prefix": balance_seed_prefix,
+    });
+
+    println!("{}", js);
+
+    Ok(())
+}
@@ -0,0 +1,257 @@
+use std::{
+    collections::BTreeMap,
+    convert::{TryFrom, TryInto},
+};
+
+use log::{debug, info, warn};
+
+use evm::{H256, U256};
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
+use solana_sdk::{account::Account, pubkey::Pubkey};
+
+use evm_loader::{
+    account::{State, FinalizedState, TAG_EMPTY},
+    transaction::{UnsignedTransaction, verify_tx_signature},
+};
+
+use crate::{
+    account_storage::{account_info, get_multiple_accounts},
+    errors::NeonCliError,
+    Config,
+    NeonCliResult,
+};
+
+/// Holder layout: tag (1 byte) | signature (65 bytes) | transaction length (8 bytes) | transaction
+pub const HOLDER_SIGNATURE_OFFSET: usize = 1;
+pub const HOLDER_TRX_LEN_OFFSET: usize = HOLDER_SIGNATURE_OFFSET + 65;
+pub const HOLDER_TRX_OFFSET: usize = HOLDER_TRX_LEN_OFFSET + 8;
+
+/// State layout: tag | caller | nonce | gas limit | gas price | slot | operator | ...
+const STATE_OPERATOR_OFFSET: usize = 1 + 20 + 8 + 32 + 32 + 8;
+
+
+/// Splits holder data into signature and unsigned transaction bytes
+pub fn holder_transaction(data: &[u8]) -> Option<([u8; 65], &[u8])> {
+    let signature: [u8; 65] = data.get(HOLDER_SIGNATURE_OFFSET..HOLDER_TRX_LEN_OFFSET)?.try_into().ok()?;
+
+    let trx_len = data.get(HOLDER_TRX_LEN_OFFSET..HOLDER_TRX_OFFSET)?;
+    let trx_len = u64::from_le_bytes(trx_len.try_into().ok()?);
+    let trx_len = usize::try_from(trx_len).ok()?;
+
+    let trx_end = HOLDER_TRX_OFFSET.checked_add(trx_len)?;
+    let trx = data.get(HOLDER_TRX_OFFSET..trx_end)?;
+
+    Some((signature, trx))
+}
+
+/// Ethereum hash of the signed transaction
+pub fn signed_transaction_hash(trx: &UnsignedTransaction, signature: &[u8; 65]) -> H256 {
+    let recovery_id = U256::from(signature[64]);
+    let v = trx.chain_id.map_or_else(
+        || recovery_id + 27,
+        |chain_id| chain_id * 2 + 35 + recovery_id
+    );
+    let r = U256::from_big_endian(&signature[0..32]);
+    let s = U256::from_big_endian(&signature[32..64]);
+
+    let mut stream = rlp::RlpStream::new_list(9);
+    stream.append(&trx.nonce);
+    stream.append(&trx.gas_price);
+    stream.append(&trx.gas_limit);
+    match trx.to.as_ref() {
+        None => stream.append(&""),
+        Some(address) => stream.append(address),
+    };
+    stream.append(&trx.value);
+    stream.append(&trx.call_data);
+    stream.append(&v);
+    stream.append(&r);
+    stream.append(&s);
+
+    crate::keccak256_h256(&stream.out())
+}
+
+fn transaction_json(signature: &[u8; 65], unsigned_trx: &[u8]) -> serde_json::Value {
+    let trx = match UnsignedTransaction::from_rlp(unsigned_trx) {
+        Ok(trx) => trx,
+        Err(e) => return serde_json::json!({ "error": format!("{:?}", e) }),
+    };
+
+    let sender = verify_tx_signature(signature, unsigned_trx)
+        .map(|address| format!("0x{}", hex::encode(address.as_bytes())))
+        .ok();
+
+    serde_json::json!({
+        "hash": format!("0x{}", hex::encode(signed_transaction_hash(&trx, signature).as_bytes())),
+        "sender": sender,
+        "nonce": trx.nonce,
+        "gas_price": trx.gas_price.to_string(),
+        "gas_limit": trx.gas_limit.to_string(),
+        "to": trx.to.map(|to| format!("0x{}", hex::encode(to.as_bytes()))),
+        "value": trx.value.to_string(),
+        "data_len": trx.call_data.len(),
+        "chain_id": trx.chain_id.map(|chain_id| chain_id.to_string()),
+    })
+}
+
+
+/// Decoded content of holder or storage account
+fn account_json(config: &Config, key: &Pubkey, mut account: Account) -> Result<serde_json::Value, NeonCliError> {
+    let data_len = account.data.len();
+    let tag = account.data.first().copied().unwrap_or(TAG_EMPTY);
+    info!("holder {}: tag {}, data len {}", key, tag, data_len);
+
+    let js = if tag == TAG_EMPTY {
+        let transaction = holder_transaction(&account.data);
+        let used = transaction.map_or(0, |(_, trx)| HOLDER_TRX_OFFSET + trx.len());
+        let transaction = transaction.map(|(signature, trx)| transaction_json(&signature, trx));
+
+        serde_json::json!({
+            "status": "holder",
+            "len": data_len,
+            "used": used,
+            "transaction": transaction,
+        })
+    } else if tag == State::TAG {
+        let info = account_info(key, &mut account);
+        let state = State::from_account(&config.evm_loader, &info)?;
+
+        let accounts: Vec<serde_json::Value> = state.accounts()?
+            .into_iter()
+            .map(|(is_writable, key)| serde_json::json!({ "key": key.to_string(), "is_writable": is_writable }))
+            .collect();
+
+        serde_json::json!({
+            "status": "active",
+            "len": data_len,
+            "executor_data_size": state.executor_data_size,
+            "evm_data_size": state.evm_data_size,
+            "caller": format!("0x{}", hex::encode(state.caller.as_bytes())),
+            "nonce": state.nonce,
+            "gas_limit": state.gas_limit.to_string(),
+            "gas_price": state.gas_price.to_string(),
+            "gas_used_and_paid": state.gas_used_and_paid.to_string(),
+            "number_of_payments": state.number_of_payments,
+            "operator": state.operator.to_string(),
+            "slot": state.slot,
+            "signature": hex::encode(&state.signature),
+            "accounts": accounts,
+        })
+    } else if tag == FinalizedState::TAG {
+        let info = account_info(key, &mut account);
+        let state = FinalizedState::from_account(&config.evm_loader, &info)?;
+
+        serde_json::json!({
+            "status": "finalized",
+            "len": data_len,
+            "sender": format!("0x{}", hex::encode(state.sender.as_bytes())),
+            "signature": hex::encode(&state.signature),
+        })
+    } else {
+        return Err(NeonCliError::AccountIncorrectType(account));
+    };
+
+    Ok(js)
+}
+
+
+pub fn execute(
+    config: &Config,
+    holder: &Pubkey,
+) -> NeonCliResult {
+    debug!("command_get_holder(config={:?}, holder={})", config, holder);
+
+    let account = config.rpc_client.get_account(holder)
+        .map_err(|_| NeonCliError::AccountNotFound(*holder))?;
+    if account.owner != config.evm_loader {
+        return Err(NeonCliError::InvalidStorageAccountOwner(account.owner));
+    }
+
+    println!("{}", account_json(config, holder, account)?);
+
+    Ok(())
+}
+
+/// Lists holder and storage accounts of the operator.
+/// Holders are found by ids `0..max_id` the proxy derives their seeds from,
+/// active storage accounts are found by the operator stored in them.
+pub fn list(
+    config: &Config,
+    operator: &Pubkey,
+    max_id: u64,
+) -> NeonCliResult {
+    debug!("command_list_holders(config={:?}, operator={}, max_id={})", config, operator, max_id);
+
+    let mut keys: BTreeMap<Pubkey, Option<u64>> = BTreeMap::new();
+    for id in 0..max_id {
+        let key = Pubkey::create_with_seed(operator, &crate::holder_seed(id), &config.evm_loader)
+            .map_err(|e| NeonCliError::StdIoError(std::io::Error::new(std::io::ErrorKind::InvalidInput, e)))?;
+        keys.insert(key, Some(id));
+    }
+
+    let key_list: Vec<Pubkey> = keys.keys().copied().collect();
+    let mut accounts = get_multiple_accounts(config, &key_list)?;
+
+    let rpc_config = RpcProgramAccountsConfig {
+        filters: Some(vec![
+            RpcFilterType::Memcmp(Memcmp {
+                offset: 0,
+                bytes: MemcmpEncodedBytes::Base58(bs58::encode([State::TAG]).into_string()),
+                encoding: None,
+            }),
+            RpcFilterType::Memcmp(Memcmp {
+                offset: STATE_OPERATOR_OFFSET,
+                bytes: MemcmpEncodedBytes::Base58(operator.to_string()),
+                encoding: None,
+            }),
+        ]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(config.commitment),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let states = config.rpc_client.get_program_accounts_with_config(&config.evm_loader, rpc_config)?;
+    info!("found {} holders and {} active state accounts", accounts.len(), states.len());
+
+    for (key, account) in states {
+        keys.entry(key).or_insert(None);
+        accounts.insert(key, account);
+    }
+
+    let mut holders = Vec::new();
+    for (key, id) in keys {
+        let account = match accounts.remove(&key) {
+            Some(account) if account.owner == config.evm_loader => account,
+            _ => continue,
+        };
+
+        let mut js = match account_json(config, &key, account) {
+            Ok(js) => js,
+            Err(e) => {
+                warn!("skip account {}: {}", key, e);
+                continue;
+            }
+        };
+        js["account"] = key.to_string().into();
+        js["holder_id"] = id.into();
+
+        holders.push(js);
+    }
+
+    let js = serde_json::json!({
+        "operator": operator.to_string(),
+        "total": holders.len(),
+        "holders": holders,
+    });
+
+    println!("{}", js);
+
+    Ok(())
+}
@@ -1,10 +1,12 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     convert::TryFrom,
+    str::FromStr,
 };
 
 use solana_sdk::{
     account_utils::StateMut,
+    pubkey::Pubkey,
     bpf_loader, bpf_loader_deprecated,
     bpf_loader_upgradeable::{self, UpgradeableLoaderState},
 };
@@ -25,6 +27,28 @@ impl CachedElfParams {
     }
 }
 
+/// Returns `token_mint` and `chain_id`, reading the missing ones from the deployed program ELF.
+/// The ELF is read only if at least one of them is not set.
+pub fn token_mint_and_chain_id(
+    config: &Config,
+    token_mint: Option<Pubkey>,
+    chain_id: Option<u64>,
+) -> (Pubkey, u64) {
+    if let (Some(token_mint), Some(chain_id)) = (token_mint, chain_id) {
+        return (token_mint, chain_id);
+    }
+
+    let cached_elf_params = CachedElfParams::new(config);
+    let token_mint = token_mint.unwrap_or_else(|| Pubkey::from_str(
+        cached_elf_params.get("NEON_TOKEN_MINT").unwrap()
+    ).unwrap());
+    let chain_id = chain_id.unwrap_or_else(|| u64::from_str(
+        cached_elf_params.get("NEON_CHAIN_ID").unwrap()
+    ).unwrap());
+
+    (token_mint, chain_id)
+}
+
 fn read_elf_parameters(
         _config: &Config,
         program_data: &[u8],
@@ -110,12 +134,55 @@ fn read_program_data_from_account(config: &Config) {
     print_elf_parameters(&elf_params);
 }
 
+/// Compares params of the local program with the deployed one
+fn diff_program_data_with_account(config: &Config,
+                                  program_location: &str) -> NeonCliResult {
+    let program_data = crate::read_program_data(program_location)?;
+    let local = read_elf_parameters(config, &program_data);
+    let deployed = read_elf_parameters_from_account(config)?;
+
+    let mut added = BTreeMap::new();
+    let mut removed = BTreeMap::new();
+    let mut changed = BTreeMap::new();
+
+    for (key, value) in &local {
+        match deployed.get(key) {
+            None => { added.insert(key, value); },
+            Some(deployed_value) if deployed_value != value => {
+                changed.insert(key, serde_json::json!({ "deployed": deployed_value, "local": value }));
+            },
+            Some(_) => (),
+        }
+    }
+    for (key, value) in &deployed {
+        if !local.contains_key(key) {
+            removed.insert(key, value);
+        }
+    }
+
+    let revision_mismatch = local.get("NEON_REVISION") != deployed.get("NEON_REVISION");
+
+    let js = serde_json::json!({
+        "equal": added.is_empty() && removed.is_empty() && changed.is_empty(),
+        "revision_mismatch": revision_mismatch,
+        "added": added,
+        "removed": removed,
+        "changed": changed,
+    });
+
+    println!("{}", js);
+
+    Ok(())
+}
+
 pub fn execute(
     config: &Config,
     program_location: Option<&str>,
+    diff: bool,
 ) -> NeonCliResult {
-    program_location.map_or_else(
-        || {read_program_data_from_account(config); Ok(())},
-        |program_location| read_program_data_from_file(config, program_location),
-    )
+    match program_location {
+        Some(program_location) if diff => diff_program_data_with_account(config, program_location),
+        Some(program_location) => read_program_data_from_file(config, program_location),
+        None => {read_program_data_from_account(config); Ok(())},
+    }
 }
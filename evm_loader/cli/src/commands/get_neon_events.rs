@@ -0,0 +1,35 @@
+use log::{debug, info};
+
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::{
+    events,
+    Config,
+    NeonCliResult,
+};
+
+
+pub fn execute(
+    config: &Config,
+    signature: &Signature,
+) -> NeonCliResult {
+    debug!("command_get_neon_events(config={:?}, signature={})", config, signature);
+
+    let transaction = config.rpc_client.get_transaction(signature, UiTransactionEncoding::Json)?;
+    let log_messages = transaction.transaction.meta
+        .and_then(|meta| meta.log_messages)
+        .unwrap_or_default();
+
+    let events = events::decode_log_messages(&config.evm_loader, &log_messages);
+    info!("transaction {}: {} events", signature, events.len());
+
+    let js = serde_json::json!({
+        "slot": transaction.slot,
+        "events": events,
+    });
+
+    println!("{}", js);
+
+    Ok(())
+}
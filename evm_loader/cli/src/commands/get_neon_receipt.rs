@@ -0,0 +1,72 @@
+use log::debug;
+
+use evm::{H160, H256, U256};
+
+use crate::{
+    commands::get_neon_trx_timeline::{find_timeline, Timeline},
+    events::NeonEvent,
+    Config,
+    NeonCliResult,
+};
+
+
+/// Exit statuses below are `Succeed`, above are `Revert`, `Error` and `Fatal`
+const EXIT_STATUS_REVERT: u8 = 0xd0;
+
+
+fn hex_h160(address: &H160) -> String {
+    format!("0x{}", hex::encode(address.as_bytes()))
+}
+
+/// Receipt of the NEON transaction restored from logs of the Solana transactions executing it.
+/// `status` and `gasUsed` are `null` while the transaction is not finished.
+pub fn execute(
+    config: &Config,
+    trx_hash: &H256,
+    sender: &H160,
+    limit: usize,
+) -> NeonCliResult {
+    debug!("command_get_neon_receipt(config={:?}, trx_hash={:?}, sender={}, limit={})", config, trx_hash, sender, limit);
+
+    let Timeline { transaction, entries, logs, result } = find_timeline(config, trx_hash, sender, limit)?;
+
+    let transaction = match transaction {
+        Some(transaction) => transaction,
+        None => {
+            println!("{}", serde_json::json!({
+                "transactionHash": format!("0x{}", hex::encode(trx_hash.as_bytes())),
+                "found": false,
+            }));
+            return Ok(());
+        }
+    };
+
+    let contract_address = match transaction.to {
+        Some(_) => None,
+        None => Some(evm_loader::utils::create_address(sender, U256::from(transaction.nonce))),
+    };
+
+    let (status, gas_used) = match result {
+        Some(NeonEvent::Return { exit_status, used_gas, .. }) => {
+            (Some(u8::from(exit_status < EXIT_STATUS_REVERT)), Some(used_gas))
+        },
+        _ => (None, None),
+    };
+
+    let js = serde_json::json!({
+        "transactionHash": format!("0x{}", hex::encode(trx_hash.as_bytes())),
+        "found": true,
+        "from": hex_h160(sender),
+        "to": transaction.to.as_ref().map(hex_h160),
+        "nonce": transaction.nonce,
+        "contractAddress": contract_address.as_ref().map(hex_h160),
+        "status": status,
+        "gasUsed": gas_used,
+        "logs": logs,
+        "solanaTransactions": entries,
+    });
+
+    println!("{}", js);
+
+    Ok(())
+}
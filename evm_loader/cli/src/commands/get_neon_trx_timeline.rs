@@ -0,0 +1,353 @@
+use std::{
+    collections::HashMap,
+    convert::{TryFrom, TryInto},
+    str::FromStr,
+};
+
+use log::{debug, info, warn};
+
+use evm::{H160, H256};
+
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{EncodedTransaction, UiMessage, UiTransactionEncoding};
+
+use evm_loader::{account::TAG_EMPTY, transaction::UnsignedTransaction};
+
+use crate::{
+    commands::get_holder::{holder_transaction, signed_transaction_hash},
+    errors::NeonCliError,
+    events::{self, NeonEvent},
+    Config,
+    NeonCliResult,
+};
+
+
+/// `EvmInstruction` tags carrying or continuing an Ethereum transaction
+pub const TAG_WRITE_HOLDER: u8 = 18;
+pub const TAG_CANCEL: u8 = 21;
+pub const TAG_EXECUTE_FROM_INSTRUCTION: u8 = 5;
+pub const TAG_BEGIN_FROM_INSTRUCTION: u8 = 19;
+pub const TAG_STEP_FROM_INSTRUCTION: u8 = 13;
+pub const TAG_BEGIN_FROM_ACCOUNT: u8 = 22;
+pub const TAG_STEP_FROM_ACCOUNT: u8 = 14;
+pub const TAG_STEP_FROM_ACCOUNT_NO_CHAINID: u8 = 27;
+pub const TAG_CONTINUE: u8 = 20;
+
+/// Instruction data before the signed transaction: collateral pool index and operator ether address
+pub const EXECUTE_FROM_INSTRUCTION_PREFIX_LEN: usize = 4 + 20;
+/// Instruction data before the signed transaction: collateral pool index, step count and operator ether address
+pub const STEP_FROM_INSTRUCTION_PREFIX_LEN: usize = 4 + 8 + 20;
+
+
+/// Neon EVM instruction of a confirmed Solana transaction
+pub struct NeonInstruction {
+    pub tag: u8,
+    pub accounts: Vec<Pubkey>,
+    pub data: Vec<u8>,
+}
+
+/// NEON transaction executed by an instruction
+#[derive(Clone, Copy)]
+pub struct NeonTransaction {
+    pub hash: H256,
+    pub nonce: u64,
+    pub to: Option<H160>,
+}
+
+impl NeonTransaction {
+    fn new(trx: &UnsignedTransaction, signature: &[u8; 65]) -> Self {
+        Self { hash: signed_transaction_hash(trx, signature), nonce: trx.nonce, to: trx.to }
+    }
+}
+
+/// Solana transactions of the NEON transaction found by the sender
+pub struct Timeline {
+    pub transaction: Option<NeonTransaction>,
+    /// Timeline entries in chronological order
+    pub entries: Vec<serde_json::Value>,
+    /// Logs of the successful Solana transactions
+    pub logs: Vec<NeonEvent>,
+    /// `RETURN` event of the finished transaction
+    pub result: Option<NeonEvent>,
+}
+
+/// Confirmed Solana transaction with decoded Neon EVM instructions
+pub struct SolanaTransaction {
+    pub signature: Signature,
+    pub slot: u64,
+    pub block_time: Option<i64>,
+    pub error: Option<String>,
+    pub instructions: Vec<NeonInstruction>,
+    pub events: Vec<NeonEvent>,
+}
+
+fn invalid_data(message: String) -> NeonCliError {
+    NeonCliError::StdIoError(std::io::Error::new(std::io::ErrorKind::InvalidData, message))
+}
+
+pub fn get_transaction(config: &Config, signature: &Signature) -> Result<SolanaTransaction, NeonCliError> {
+    let transaction = config.rpc_client.get_transaction(signature, UiTransactionEncoding::Json)?;
+    let meta = transaction.transaction.meta;
+
+    let message = match transaction.transaction.transaction {
+        EncodedTransaction::Json(ui_transaction) => match ui_transaction.message {
+            UiMessage::Raw(message) => message,
+            UiMessage::Parsed(_) => return Err(invalid_data(format!("{}: unexpected parsed message", signature))),
+        },
+        _ => return Err(invalid_data(format!("{}: unexpected transaction encoding", signature))),
+    };
+
+    let account_keys = message.account_keys.iter()
+        .map(|key| Pubkey::from_str(key).map_err(|e| invalid_data(format!("{}: {}", key, e))))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut instructions = Vec::new();
+    for instruction in message.instructions {
+        if account_keys[usize::from(instruction.program_id_index)] != config.evm_loader {
+            continue;
+        }
+
+        let data = bs58::decode(&instruction.data).into_vec()
+            .map_err(|e| invalid_data(format!("{}: {}", signature, e)))?;
+        let (&tag, data) = match data.split_first() {
+            Some(split) => split,
+            None => continue,
+        };
+
+        instructions.push(NeonInstruction {
+            tag,
+            accounts: instruction.accounts.iter().map(|&index| account_keys[usize::from(index)]).collect(),
+            data: data.to_vec(),
+        });
+    }
+
+    let (error, log_messages) = meta.map_or((None, Vec::new()), |meta| {
+        (meta.err.map(|e| e.to_string()), meta.log_messages.unwrap_or_default())
+    });
+
+    Ok(SolanaTransaction {
+        signature: *signature,
+        slot: transaction.slot,
+        block_time: transaction.block_time,
+        error,
+        instructions,
+        events: events::decode_log_messages(&config.evm_loader, &log_messages),
+    })
+}
+
+/// Signatures of the address in chronological order
+fn get_signatures(config: &Config, address: &Pubkey, before: Option<Signature>, limit: usize) -> Result<Vec<Signature>, NeonCliError> {
+    let signatures = config.rpc_client.get_signatures_for_address_with_config(
+        address,
+        GetConfirmedSignaturesForAddress2Config {
+            before,
+            until: None,
+            limit: Some(limit),
+            commitment: Some(config.commitment),
+        }
+    )?;
+
+    signatures.iter().rev()
+        .map(|status| Signature::from_str(&status.signature)
+            .map_err(|e| invalid_data(format!("{}: {}", status.signature, e))))
+        .collect()
+}
+
+/// Signature and unsigned transaction passed in the instruction data after the given prefix
+#[must_use]
+pub fn instruction_transaction(data: &[u8], prefix_len: usize) -> Option<([u8; 65], &[u8])> {
+    let signature: [u8; 65] = data.get(prefix_len..prefix_len + 65)?.try_into().ok()?;
+    let unsigned_trx = data.get(prefix_len + 65..)?;
+
+    Some((signature, unsigned_trx))
+}
+
+/// Transaction passed in the instruction data after the given prefix
+fn instruction_neon_transaction(data: &[u8], prefix_len: usize) -> Option<NeonTransaction> {
+    let (signature, unsigned_trx) = instruction_transaction(data, prefix_len)?;
+    let trx = UnsignedTransaction::from_rlp(unsigned_trx).ok()?;
+
+    Some(NeonTransaction::new(&trx, &signature))
+}
+
+/// Restores holder content at the moment of the `before` transaction by replaying holder writes.
+/// Returns the holder data and the holder write transactions.
+pub fn restore_holder(config: &Config, holder: &Pubkey, before: Signature, limit: usize) -> Result<(Vec<u8>, Vec<SolanaTransaction>), NeonCliError> {
+    let mut data = vec![TAG_EMPTY];
+    let mut writes = Vec::new();
+
+    for signature in get_signatures(config, holder, Some(before), limit)? {
+        let transaction = get_transaction(config, &signature)?;
+        if transaction.error.is_some() {
+            continue;
+        }
+
+        let mut is_write = false;
+        for instruction in &transaction.instructions {
+            if instruction.tag != TAG_WRITE_HOLDER || instruction.accounts.first() != Some(holder) || instruction.data.len() < 8 + 4 + 8 {
+                continue;
+            }
+
+            let offset = u32::from_le_bytes(instruction.data[8..12].try_into().expect("4 bytes"));
+            let begin = 1 + usize::try_from(offset).expect("usize is 8 bytes");
+            let bytes = &instruction.data[8 + 4 + 8..];
+            if data.len() < begin + bytes.len() {
+                data.resize(begin + bytes.len(), 0);
+            }
+            data[begin..begin + bytes.len()].copy_from_slice(bytes);
+            is_write = true;
+        }
+
+        if is_write {
+            writes.push(transaction);
+        }
+    }
+
+    Ok((data, writes))
+}
+
+/// Transaction stored in the holder at the moment of the `before` transaction
+/// and the holder write transactions.
+fn holder_neon_transaction(config: &Config, holder: &Pubkey, before: Signature, limit: usize) -> Result<Option<(NeonTransaction, Vec<SolanaTransaction>)>, NeonCliError> {
+    let (data, writes) = restore_holder(config, holder, before, limit)?;
+
+    let neon_trx = holder_transaction(&data).and_then(|(signature, trx)| {
+        let trx = UnsignedTransaction::from_rlp(trx).ok()?;
+        Some(NeonTransaction::new(&trx, &signature))
+    });
+
+    Ok(neon_trx.map(|neon_trx| (neon_trx, writes)))
+}
+
+fn timeline_entry(transaction: &SolanaTransaction, step: &str, account: &Pubkey) -> serde_json::Value {
+    serde_json::json!({
+        "signature": transaction.signature.to_string(),
+        "slot": transaction.slot,
+        "block_time": transaction.block_time,
+        "step": step,
+        "account": account.to_string(),
+        "error": transaction.error,
+    })
+}
+
+
+/// Scans `limit` latest transactions of the sender account for the NEON transaction
+pub fn find_timeline(
+    config: &Config,
+    trx_hash: &H256,
+    sender: &H160,
+    limit: usize,
+) -> Result<Timeline, NeonCliError> {
+    // Sender account is written by every instruction executing its transactions
+    let (sender_solana, _) = crate::make_solana_program_address(sender, &config.evm_loader);
+    let signatures = get_signatures(config, &sender_solana, None, limit)?;
+    info!("sender {} => {}: {} signatures", sender, sender_solana, signatures.len());
+
+    // Transaction currently executed in the state account
+    let mut active: HashMap<Pubkey, NeonTransaction> = HashMap::new();
+    let mut target: Option<NeonTransaction> = None;
+    let mut timeline = Vec::new();
+    let mut logs = Vec::new();
+    let mut result: Option<NeonEvent> = None;
+
+    for signature in signatures {
+        let transaction = get_transaction(config, &signature)?;
+
+        for instruction in &transaction.instructions {
+            let (step, storage, neon_trx) = match instruction.tag {
+                TAG_EXECUTE_FROM_INSTRUCTION => {
+                    ("execute", None, instruction_neon_transaction(&instruction.data, EXECUTE_FROM_INSTRUCTION_PREFIX_LEN))
+                },
+                TAG_BEGIN_FROM_INSTRUCTION | TAG_STEP_FROM_INSTRUCTION => {
+                    let storage = instruction.accounts.first().copied();
+                    ("step", storage, instruction_neon_transaction(&instruction.data, STEP_FROM_INSTRUCTION_PREFIX_LEN))
+                },
+                TAG_BEGIN_FROM_ACCOUNT | TAG_STEP_FROM_ACCOUNT | TAG_STEP_FROM_ACCOUNT_NO_CHAINID => {
+                    let holder = instruction.accounts.first().copied();
+                    let storage = instruction.accounts.get(1).copied();
+
+                    let mut neon_trx = storage.and_then(|storage| active.get(&storage).copied());
+                    if let (None, Some(holder)) = (neon_trx, holder) {
+                        if let Some((holder_trx, writes)) = holder_neon_transaction(config, &holder, signature, limit)? {
+                            if holder_trx.hash == *trx_hash {
+                                timeline.extend(writes.iter().map(|write| timeline_entry(write, "write_holder", &holder)));
+                            }
+                            neon_trx = Some(holder_trx);
+                        }
+                    }
+
+                    ("step", storage, neon_trx)
+                },
+                TAG_CONTINUE => {
+                    let storage = instruction.accounts.first().copied();
+                    ("continue", storage, storage.and_then(|storage| active.get(&storage).copied()))
+                },
+                TAG_CANCEL => {
+                    let storage = instruction.accounts.first().copied();
+                    ("cancel", storage, storage.and_then(|storage| active.get(&storage).copied()))
+                },
+                _ => continue,
+            };
+
+            let neon_trx = match neon_trx {
+                Some(neon_trx) => neon_trx,
+                None => {
+                    warn!("{}: unknown transaction of instruction {}", signature, instruction.tag);
+                    continue;
+                }
+            };
+
+            // Failed Solana transactions do not change the state account
+            let finished = transaction.events.iter().find(|e| matches!(e, NeonEvent::Return { .. }));
+            if let (Some(storage), None) = (storage, &transaction.error) {
+                if finished.is_some() || step == "cancel" {
+                    active.remove(&storage);
+                } else {
+                    active.insert(storage, neon_trx);
+                }
+            }
+
+            if neon_trx.hash != *trx_hash {
+                continue;
+            }
+
+            target = Some(neon_trx);
+            let account = storage.unwrap_or(sender_solana);
+            timeline.push(timeline_entry(&transaction, step, &account));
+
+            if transaction.error.is_none() {
+                logs.extend(transaction.events.iter().filter(|e| matches!(e, NeonEvent::Log { .. })).cloned());
+                if finished.is_some() {
+                    result = finished.cloned();
+                }
+            }
+        }
+    }
+
+    Ok(Timeline { transaction: target, entries: timeline, logs, result })
+}
+
+
+pub fn execute(
+    config: &Config,
+    trx_hash: &H256,
+    sender: &H160,
+    limit: usize,
+) -> NeonCliResult {
+    debug!("command_get_neon_trx_timeline(config={:?}, trx_hash={:?}, sender={}, limit={})", config, trx_hash, sender, limit);
+
+    let Timeline { entries: timeline, result, .. } = find_timeline(config, trx_hash, sender, limit)?;
+
+    let js = serde_json::json!({
+        "hash": format!("0x{}", hex::encode(trx_hash.as_bytes())),
+        "sender": format!("0x{}", hex::encode(sender.as_bytes())),
+        "found": !timeline.is_empty(),
+        "timeline": timeline,
+        "result": result,
+    });
+
+    println!("{}", js);
+
+    Ok(())
+}
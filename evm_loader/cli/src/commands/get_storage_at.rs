@@ -1,56 +1,140 @@
-use solana_sdk::{ pubkey::Pubkey };
+use std::collections::HashMap;
+
+use solana_sdk::{ account::Account, pubkey::Pubkey };
 
 use evm::{H160, U256};
 
 use evm_loader::{
-    account::{EthereumStorage, EthereumContract, ACCOUNT_SEED_VERSION},
+    account::{EthereumAccount, EthereumStorage, EthereumContract, ACCOUNT_SEED_VERSION},
     config::STORAGE_ENTIRIES_IN_CONTRACT_ACCOUNT,
 };
 
 use crate::{
-    account_storage::{EmulatorAccountStorage, account_info },
+    account_storage::{make_solana_program_address, account_info, get_multiple_accounts},
+    errors::NeonCliError,
     Config,
+    NeonCliResult,
 };
 
 
+/// Contract generation and storage stored in the contract account
+fn read_contract(config: &Config, key: &Pubkey, account: &mut Account) -> Option<(u32, Vec<u8>)> {
+    let info = account_info(key, account);
+    let contract = EthereumContract::from_account(&config.evm_loader, &info).ok()?;
 
-pub fn execute(
-    config: &Config,
-    ether_address: H160,
-    index: &U256
-) {
-    let value = if let Some((_, Some(mut code_account))) =  EmulatorAccountStorage::get_account_from_solana(config, &ether_address) {
-        let code_key = Pubkey::default();
-        let code_info = account_info(&code_key, &mut code_account);
+    Some((contract.generation, contract.extension.storage.to_vec()))
+}
+
+fn read_storage_cell(config: &Config, key: &Pubkey, account: &mut Account) -> U256 {
+    if solana_sdk::system_program::check_id(&account.owner) {
+        return U256::zero();
+    }
+
+    let info = account_info(key, account);
+    EthereumStorage::from_account(&config.evm_loader, &info)
+        .map_or_else(|_| U256::zero(), |storage| storage.value)
+}
+
+/// Reads storage values of several contracts.
+/// Each kind of account (ethereum, contract, storage cell) is downloaded in one pass.
+pub fn read_storage(config: &Config, slots: &[(H160, U256)]) -> Result<Vec<U256>, NeonCliError> {
+    let mut addresses: Vec<H160> = slots.iter().map(|(address, _)| *address).collect();
+    addresses.sort_unstable();
+    addresses.dedup();
 
-        let contract = EthereumContract::from_account(&config.evm_loader, &code_info).unwrap();
+    let ether_keys: Vec<Pubkey> = addresses.iter()
+        .map(|address| make_solana_program_address(address, &config.evm_loader).0)
+        .collect();
+    let mut ether_accounts = get_multiple_accounts(config, &ether_keys)?;
 
+    let mut code_keys: HashMap<H160, Pubkey> = HashMap::new();
+    for (address, key) in addresses.iter().zip(&ether_keys) {
+        if let Some(account) = ether_accounts.get_mut(key) {
+            let info = account_info(key, account);
+            if let Some(code_account) = EthereumAccount::from_account(&config.evm_loader, &info).ok().and_then(|a| a.code_account) {
+                code_keys.insert(*address, code_account);
+            }
+        }
+    }
+
+    let code_accounts_keys: Vec<Pubkey> = code_keys.values().copied().collect();
+    let mut code_accounts = get_multiple_accounts(config, &code_accounts_keys)?;
+
+    let mut contracts: HashMap<H160, (u32, Vec<u8>)> = HashMap::new();
+    for (address, key) in &code_keys {
+        if let Some(contract) = code_accounts.get_mut(key).and_then(|account| read_contract(config, key, account)) {
+            contracts.insert(*address, contract);
+        }
+    }
+
+    let mut cell_keys: HashMap<(H160, U256), Pubkey> = HashMap::new();
+    for (address, index) in slots {
         if *index < U256::from(STORAGE_ENTIRIES_IN_CONTRACT_ACCOUNT) {
-            let index: usize = index.as_usize() * 32;
-            U256::from_big_endian(&contract.extension.storage[index..index+32])
-        } else {
+            continue;
+        }
+
+        if let Some((generation, _)) = contracts.get(address) {
             let mut index_bytes = [0_u8; 32];
             index.to_little_endian(&mut index_bytes);
-            let seeds: &[&[u8]] = &[&[ACCOUNT_SEED_VERSION], b"ContractStorage", ether_address.as_bytes(), &contract.generation.to_le_bytes(), &index_bytes];
-        
-            let (address, _) = Pubkey::find_program_address(seeds, &config.evm_loader);
-        
-            if let Ok(mut account) = config.rpc_client.get_account(&address) {
-                if solana_sdk::system_program::check_id(&account.owner) {
-                    U256::zero()
-                } else {
-                    let account_info = account_info(&address, &mut account);
-                    let storage = EthereumStorage::from_account(&config.evm_loader, &account_info).unwrap();
-                    storage.value
-                }
-            } else {
-                U256::zero()
-            }
+            let seeds: &[&[u8]] = &[&[ACCOUNT_SEED_VERSION], b"ContractStorage", address.as_bytes(), &generation.to_le_bytes(), &index_bytes];
+
+            let (key, _) = Pubkey::find_program_address(seeds, &config.evm_loader);
+            cell_keys.insert((*address, *index), key);
         }
-    } else {
-        U256::zero()
-    };
+    }
 
-    print!("{:#x}", value);
+    let cell_accounts_keys: Vec<Pubkey> = cell_keys.values().copied().collect();
+    let mut cell_accounts = get_multiple_accounts(config, &cell_accounts_keys)?;
+
+    let values = slots.iter()
+        .map(|(address, index)| {
+            let (_, storage) = match contracts.get(address) {
+                Some(contract) => contract,
+                None => return U256::zero(),
+            };
+
+            if *index < U256::from(STORAGE_ENTIRIES_IN_CONTRACT_ACCOUNT) {
+                let index: usize = index.as_usize() * 32;
+                return U256::from_big_endian(&storage[index..index+32]);
+            }
+
+            let key = cell_keys[&(*address, *index)];
+            cell_accounts.get_mut(&key)
+                .map_or_else(U256::zero, |account| read_storage_cell(config, &key, account))
+        })
+        .collect();
+
+    Ok(values)
 }
 
+
+pub fn execute(
+    config: &Config,
+    ether_address: H160,
+    index: &U256
+) -> NeonCliResult {
+    let values = read_storage(config, &[(ether_address, *index)])?;
+
+    print!("{:#x}", values[0]);
+
+    Ok(())
+}
+
+pub fn execute_batch(
+    config: &Config,
+    slots: &[(H160, U256)],
+) -> NeonCliResult {
+    let values = read_storage(config, slots)?;
+
+    let js: Vec<serde_json::Value> = slots.iter().zip(values)
+        .map(|((address, index), value)| serde_json::json!({
+            "address": format!("0x{}", hex::encode(address.as_bytes())),
+            "index": format!("{:#x}", index),
+            "value": format!("{:#x}", value),
+        }))
+        .collect();
+
+    println!("{}", serde_json::Value::Array(js));
+
+    Ok(())
+}
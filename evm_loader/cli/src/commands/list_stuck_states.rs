@@ -0,0 +1,105 @@
+use std::collections::BTreeMap;
+
+use log::{debug, info, warn};
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, MemcmpEncodedBytes, RpcFilterType},
+};
+use solana_sdk::transaction::Transaction;
+
+use evm_loader::account::State;
+
+use crate::{
+    account_storage::account_info,
+    commands::cancel_trx,
+    errors::NeonCliError,
+    Config,
+    NeonCliResult,
+};
+
+
+pub fn execute(
+    config: &Config,
+    min_age: u64,
+    with_cancel: bool,
+) -> NeonCliResult {
+    debug!("command_list_stuck_states(config={:?}, min_age={}, with_cancel={})", config, min_age, with_cancel);
+
+    let current_slot = config.rpc_client.get_slot_with_commitment(config.commitment)?;
+
+    let tag = bs58::encode([State::TAG]).into_string();
+    let rpc_config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp {
+            offset: 0,
+            bytes: MemcmpEncodedBytes::Base58(tag),
+            encoding: None,
+        })]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(config.commitment),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+    let accounts = config.rpc_client.get_program_accounts_with_config(&config.evm_loader, rpc_config)?;
+    info!("found {} state accounts", accounts.len());
+
+    let mut operators: BTreeMap<String, Vec<serde_json::Value>> = BTreeMap::new();
+    for (key, mut account) in accounts {
+        let info = account_info(&key, &mut account);
+        let storage = match State::from_account(&config.evm_loader, &info) {
+            Ok(storage) => storage,
+            Err(e) => {
+                warn!("skip state account {}: {}", key, e);
+                continue;
+            }
+        };
+
+        let age = current_slot.saturating_sub(storage.slot);
+        if age < min_age {
+            continue;
+        }
+
+        let locked_accounts: Vec<serde_json::Value> = storage.accounts()?
+            .into_iter()
+            .map(|(is_writable, pubkey)| serde_json::json!({
+                "pubkey": pubkey.to_string(),
+                "is_writable": is_writable,
+            }))
+            .collect();
+
+        let mut js = serde_json::json!({
+            "account": key.to_string(),
+            "caller": format!("0x{}", hex::encode(storage.caller.as_bytes())),
+            "nonce": storage.nonce,
+            "slot": storage.slot,
+            "age": age,
+            "gas_used_and_paid": storage.gas_used_and_paid.to_string(),
+            "signature": hex::encode(storage.signature),
+            "locked_accounts": locked_accounts,
+        });
+
+        if with_cancel {
+            let instructions = cancel_trx::cancel_instructions(config, &key, &storage)?;
+            let transaction = Transaction::new_with_payer(&instructions, Some(&config.signer.pubkey()));
+            let serialized = bincode::serialize(&transaction)
+                .map_err(|e| NeonCliError::StdIoError(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
+            js["cancel_transaction"] = base64::encode(serialized).into();
+        }
+
+        operators.entry(storage.operator.to_string()).or_default().push(js);
+    }
+
+    let total: usize = operators.values().map(Vec::len).sum();
+    let js = serde_json::json!({
+        "current_slot": current_slot,
+        "total": total,
+        "operators": operators,
+    });
+
+    println!("{}", js);
+
+    Ok(())
+}
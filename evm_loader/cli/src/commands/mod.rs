@@ -1,11 +1,27 @@
 pub mod cancel_trx;
+pub mod check_account_for_fee;
+pub mod create_access_list;
+pub mod create_lookup_table;
 pub mod create_ether_account;
 pub mod create_program_address;
 pub mod deploy;
 pub mod deposit;
 pub mod migrate_account;
 pub mod emulate;
+pub mod emulate_solana_trx;
+pub mod estimate_gas;
+pub mod get_balance;
 pub mod get_ether_account_data;
+pub mod get_create2_address;
+pub mod get_erc20_binding;
+pub mod get_holder;
+pub mod get_neon_events;
+pub mod get_neon_receipt;
+pub mod get_neon_trx_timeline;
 pub mod get_neon_elf;
 pub mod get_storage_at;
+pub mod list_stuck_states;
+pub mod test_env;
 pub mod update_valids_table;
+pub mod verify_holder;
+pub mod withdraw_operator;
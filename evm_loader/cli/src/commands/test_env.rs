@@ -0,0 +1,214 @@
+use std::{
+    fs::{self, File},
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use log::{debug, info, warn};
+
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    native_token::sol_to_lamports,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Signer},
+};
+
+use crate::{
+    errors::NeonCliError,
+    Config,
+    NeonCliResult,
+};
+
+
+const PID_FILE: &str = "neon-test-env.pid";
+const LOG_FILE: &str = "neon-test-env.log";
+/// Home directory of the deploy scripts inside the ledger
+const SCRIPTS_HOME: &str = "home";
+const EVM_LOADER_KEYPAIR: &str = "evm_loader-keypair.json";
+const VALIDATOR_START_TIMEOUT: Duration = Duration::from_secs(60);
+
+
+fn command_error(command: &str, status: std::process::ExitStatus) -> NeonCliError {
+    NeonCliError::StdIoError(Error::new(ErrorKind::Other, format!("{} exited with {}", command, status)))
+}
+
+fn wait_for_validator(rpc_client: &RpcClient) -> Result<(), NeonCliError> {
+    let started = Instant::now();
+    loop {
+        match rpc_client.get_version() {
+            Ok(version) => {
+                info!("solana-test-validator {} is ready", version.solana_core);
+                return Ok(());
+            },
+            Err(e) if started.elapsed() > VALIDATOR_START_TIMEOUT => return Err(e.into()),
+            Err(_) => sleep(Duration::from_secs(1)),
+        }
+    }
+}
+
+/// Checks that the environment can be deployed for the configured program and operator
+fn check_config(config: &Config, scripts_dir: &Path) -> Result<(), NeonCliError> {
+    let operator = config.signer.pubkey();
+    if !evm_loader::config::AUTHORIZED_OPERATOR_LIST.contains(&operator) {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("signer {} is not an authorized operator", operator)).into());
+    }
+
+    let keypair_path = scripts_dir.join(EVM_LOADER_KEYPAIR);
+    let keypair = read_keypair_file(&keypair_path)
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, format!("{}: {}", keypair_path.display(), e)))?;
+    if keypair.pubkey() != config.evm_loader {
+        return Err(Error::new(ErrorKind::InvalidInput, format!("{} is the keypair of {}, expected evm_loader {}",
+            keypair_path.display(), keypair.pubkey(), config.evm_loader)).into());
+    }
+
+    Ok(())
+}
+
+/// Home directory with a copy of the user's Solana CLI config.
+/// `deploy-evm.sh` runs `solana config set`, which must not change the user's config.
+fn scripts_home(ledger: &Path) -> Result<PathBuf, NeonCliError> {
+    let cli_config = solana_cli_config::CONFIG_FILE.as_ref()
+        .and_then(|config_file| solana_cli_config::Config::load(config_file).ok())
+        .unwrap_or_default();
+
+    let home = ledger.join(SCRIPTS_HOME);
+    let config_file = home.join(".config").join("solana").join("cli").join("config.yml");
+    cli_config.save(&config_file.to_string_lossy())?;
+
+    Ok(home)
+}
+
+fn stop_validator(validator: &mut Child, pid_file: &Path) {
+    if let Err(e) = validator.kill().and_then(|_| validator.wait()) {
+        warn!("failed to stop solana-test-validator, pid {}: {}", validator.id(), e);
+    }
+    if let Err(e) = fs::remove_file(pid_file) {
+        warn!("failed to remove {}: {}", pid_file.display(), e);
+    }
+}
+
+/// Creates Neon EVM accounts on the started validator and funds the operator
+fn setup(
+    config: &Config,
+    rpc_client: &RpcClient,
+    solana_url: &str,
+    ledger: &Path,
+    scripts_dir: &Path,
+    airdrop_sol: f64,
+) -> Result<(), NeonCliError> {
+    wait_for_validator(rpc_client)?;
+
+    // Neon EVM is deployed in genesis, the script creates tokens, collateral and token pools
+    let status = Command::new("./deploy-evm.sh")
+        .current_dir(scripts_dir)
+        .env("HOME", scripts_home(ledger)?)
+        .env("SOLANA_URL", solana_url)
+        .env("SKIP_EVM_DEPLOY", "YES")
+        .status()?;
+    if !status.success() {
+        return Err(command_error("deploy-evm.sh", status));
+    }
+
+    let operator = config.signer.pubkey();
+    airdrop(rpc_client, &operator, airdrop_sol)?;
+    info!("operator {} funded with {} SOL", operator, airdrop_sol);
+
+    Ok(())
+}
+
+fn airdrop(rpc_client: &RpcClient, pubkey: &Pubkey, sol: f64) -> Result<(), NeonCliError> {
+    let signature = rpc_client.request_airdrop(pubkey, sol_to_lamports(sol))?;
+    while !rpc_client.confirm_transaction(&signature)? {
+        sleep(Duration::from_millis(500));
+    }
+
+    Ok(())
+}
+
+/// Starts `solana-test-validator` with Neon EVM in genesis,
+/// creates tokens and pools with `deploy-evm.sh` and funds the operator
+pub fn up(
+    config: &Config,
+    ledger: &Path,
+    scripts_dir: &Path,
+    rpc_port: u16,
+    airdrop_sol: f64,
+) -> NeonCliResult {
+    debug!("command_test_env_up(config={:?}, ledger={:?}, scripts_dir={:?}, rpc_port={}, airdrop_sol={})",
+        config, ledger, scripts_dir, rpc_port, airdrop_sol);
+
+    let pid_file = ledger.join(PID_FILE);
+    if pid_file.exists() {
+        return Err(Error::new(ErrorKind::AlreadyExists, format!("test environment is already running: {}", pid_file.display())).into());
+    }
+
+    let program_location = scripts_dir.join("evm_loader.so");
+    if !program_location.exists() {
+        return Err(Error::new(ErrorKind::NotFound, format!("{} not found", program_location.display())).into());
+    }
+
+    check_config(config, scripts_dir)?;
+
+    fs::create_dir_all(ledger)?;
+    let log = File::create(ledger.join(LOG_FILE))?;
+
+    let mut validator = Command::new("solana-test-validator")
+        .arg("--reset")
+        .arg("--quiet")
+        .arg("--ledger").arg(ledger)
+        .arg("--rpc-port").arg(rpc_port.to_string())
+        .arg("--bpf-program").arg(config.evm_loader.to_string()).arg(&program_location)
+        .stdin(Stdio::null())
+        .stdout(log.try_clone()?)
+        .stderr(log)
+        .spawn()?;
+
+    fs::write(&pid_file, validator.id().to_string())?;
+    info!("solana-test-validator started, pid {}", validator.id());
+
+    let solana_url = format!("http://127.0.0.1:{}", rpc_port);
+    let websocket_url = format!("ws://127.0.0.1:{}", rpc_port + 1);
+    let rpc_client = RpcClient::new_with_commitment(solana_url.clone(), config.commitment);
+    if let Err(e) = setup(config, &rpc_client, &solana_url, ledger, scripts_dir, airdrop_sol) {
+        stop_validator(&mut validator, &pid_file);
+        return Err(e);
+    }
+
+    let js = serde_json::json!({
+        "solana_url": solana_url,
+        "websocket_url": websocket_url,
+        "evm_loader": config.evm_loader.to_string(),
+        "operator": config.signer.pubkey().to_string(),
+        "ledger": ledger.display().to_string(),
+        "pid": validator.id(),
+    });
+
+    println!("{}", js);
+
+    Ok(())
+}
+
+/// Stops `solana-test-validator` started by `up`
+pub fn down(
+    config: &Config,
+    ledger: &Path,
+) -> NeonCliResult {
+    debug!("command_test_env_down(config={:?}, ledger={:?})", config, ledger);
+
+    let pid_file = ledger.join(PID_FILE);
+    let pid = fs::read_to_string(&pid_file)?;
+    let pid = pid.trim();
+
+    let status = Command::new("kill").arg(pid).status()?;
+    if !status.success() {
+        return Err(command_error("kill", status));
+    }
+
+    fs::remove_file(&pid_file)?;
+    info!("solana-test-validator stopped, pid {}", pid);
+
+    Ok(())
+}
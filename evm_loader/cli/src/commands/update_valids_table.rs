@@ -1,15 +1,6 @@
-use log::{ debug, info, trace };
+use log::{ info, trace };
 
-use solana_sdk::{
-    instruction::{AccountMeta, Instruction},
-    message::Message,
-    transaction::Transaction,
-    compute_budget::ComputeBudgetInstruction,
-};
-
-use solana_cli::{
-    checks::check_account_for_fee,
-};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
 
 use evm::{H160};
 
@@ -19,7 +10,8 @@ use evm_loader::{
         COMPUTE_BUDGET_UNITS,
         COMPUTE_BUDGET_HEAP_FRAME,
         REQUEST_UNITS_ADDITIONAL_FEE,
-    }
+    },
+    instruction_builder,
 };
 
 use crate::{
@@ -29,6 +21,7 @@ use crate::{
         account_info,
     },
     errors::NeonCliError,
+    transaction_signer::send_instructions,
     Config,
     NeonCliResult,
 };
@@ -52,11 +45,7 @@ pub fn execute(config: &Config, ether_address: H160) -> NeonCliResult {
     info!("code account: {:?}", code_account);
 
     let update_valids_table_instruction =
-        Instruction::new_with_bincode(
-            config.evm_loader,
-            &(23_u8), // TODO remove magic number
-            vec![AccountMeta::new(code_account, false)]
-        );
+        instruction_builder::update_valids_table(&config.evm_loader, &code_account);
 
     let instructions = vec![
         ComputeBudgetInstruction::request_units(COMPUTE_BUDGET_UNITS, REQUEST_UNITS_ADDITIONAL_FEE),
@@ -64,23 +53,7 @@ pub fn execute(config: &Config, ether_address: H160) -> NeonCliResult {
         update_valids_table_instruction
     ];
 
-    let mut finalize_message = Message::new(&instructions, Some(&config.signer.pubkey()));
-    let blockhash = config.rpc_client.get_latest_blockhash()?;
-    info!("blockhash: {:?}", blockhash);
-    finalize_message.recent_blockhash = blockhash;
-
-    check_account_for_fee(
-        &config.rpc_client,
-        &config.signer.pubkey(),
-        &finalize_message
-    )?;
-
-    let mut finalize_tx = Transaction::new_unsigned(finalize_message);
-
-    finalize_tx.try_sign(&[&*config.signer], blockhash)?;
-    debug!("signed: {:x?}", finalize_tx);
-
-    config.rpc_client.send_and_confirm_transaction_with_spinner(&finalize_tx)?;
+    send_instructions(config, &instructions, config.rpc_client.commitment())?;
 
     Ok(())
 }
@@ -0,0 +1,94 @@
+use std::convert::TryFrom;
+
+use log::{debug, info};
+
+use evm::H256;
+
+use solana_sdk::pubkey::Pubkey;
+
+use evm_loader::{
+    account::TAG_EMPTY,
+    transaction::UnsignedTransaction,
+};
+
+use crate::{
+    commands::get_holder::{holder_transaction, signed_transaction_hash},
+    errors::NeonCliError,
+    Config,
+    NeonCliResult,
+};
+
+
+/// Chunks of holder data (after the tag) that contain only zeroes.
+/// Legitimate transaction data may contain such chunks too,
+/// so this is only a hint at unwritten ranges when the hash does not match.
+fn missing_chunks(data: &[u8], end: usize, chunk_size: usize) -> Vec<serde_json::Value> {
+    let end = end.min(data.len());
+    let written = &data[1..end];
+
+    written.chunks(chunk_size)
+        .enumerate()
+        .filter(|(_, chunk)| chunk.iter().all(|b| *b == 0))
+        .map(|(index, chunk)| serde_json::json!({
+            "index": index,
+            "offset": index * chunk_size,
+            "len": chunk.len(),
+        }))
+        .collect()
+}
+
+
+pub fn execute(
+    config: &Config,
+    holder: &Pubkey,
+    expected_hash: &H256,
+    chunk_size: u64,
+) -> NeonCliResult {
+    debug!("command_verify_holder(config={:?}, holder={}, expected_hash={:?})", config, holder, expected_hash);
+
+    let account = config.rpc_client.get_account(holder)
+        .map_err(|_| NeonCliError::AccountNotFound(*holder))?;
+    if account.owner != config.evm_loader {
+        return Err(NeonCliError::InvalidStorageAccountOwner(account.owner));
+    }
+
+    if account.data.first().copied() != Some(TAG_EMPTY) {
+        return Err(NeonCliError::AccountIncorrectType(account));
+    }
+
+    let chunk_size = usize::try_from(chunk_size).unwrap().max(1);
+
+    let (trx_end, actual_hash, error) = match holder_transaction(&account.data) {
+        Some((signature, trx)) => {
+            let trx_end = 1 + 65 + 8 + trx.len();
+            match UnsignedTransaction::from_rlp(trx) {
+                Ok(trx) => (trx_end, Some(signed_transaction_hash(&trx, &signature)), None),
+                Err(e) => (trx_end, None, Some(format!("Invalid transaction RLP: {:?}", e))),
+            }
+        },
+        None => (account.data.len(), None, Some("Transaction length exceeds holder size".to_string())),
+    };
+
+    let valid = actual_hash.as_ref() == Some(expected_hash);
+    let missing = if valid {
+        Vec::new()
+    } else {
+        missing_chunks(&account.data, trx_end, chunk_size)
+    };
+    info!("holder {}: valid {}, {} missing chunks", holder, valid, missing.len());
+
+    let js = serde_json::json!({
+        "holder": holder.to_string(),
+        "valid": valid,
+        "expected_hash": format!("0x{}", hex::encode(expected_hash.as_bytes())),
+        "actual_hash": actual_hash.map(|hash| format!("0x{}", hex::encode(hash.as_bytes()))),
+        "error": error,
+        "written_len": trx_end - 1,
+        "chunk_size": chunk_size,
+        "missing_chunks": missing,
+    });
+
+    println!("{}", js);
+
+    Ok(())
+}
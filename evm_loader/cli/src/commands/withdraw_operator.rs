@@ -0,0 +1,77 @@
+use log::{debug, info};
+
+use solana_sdk::{
+    pubkey::Pubkey,
+    compute_budget::ComputeBudgetInstruction,
+};
+
+use evm_loader::{
+    config::{
+        COMPUTE_BUDGET_UNITS,
+        COMPUTE_BUDGET_HEAP_FRAME,
+        REQUEST_UNITS_ADDITIONAL_FEE,
+    },
+    instruction::operator_balance_withdraw::operator_balance_address,
+    instruction_builder,
+};
+
+use crate::{
+    transaction_signer::send_instructions,
+    Config,
+    NeonCliResult,
+};
+
+
+/// Executes subcommand `withdraw-operator`.
+/// Moves `amount` NEON tokens earned by the signer operator to `destination`,
+/// associated token account of the signer by default.
+/// The earnings are collected on the operator balance Ether account, see `operator_balance_address`.
+pub fn execute(
+    config: &Config,
+    amount: u64,
+    destination: Option<Pubkey>,
+) -> NeonCliResult {
+    debug!("command_withdraw_operator(config={:?}, amount={}, destination={:?})", config, amount, destination);
+
+    let signer = config.signer.pubkey();
+    let balance_address = operator_balance_address(&signer);
+
+    let token_mint_id = evm_loader::config::token_mint::id();
+    let mut instructions = Vec::with_capacity(4);
+
+    instructions.push(ComputeBudgetInstruction::request_units(COMPUTE_BUDGET_UNITS, REQUEST_UNITS_ADDITIONAL_FEE));
+    instructions.push(ComputeBudgetInstruction::request_heap_frame(COMPUTE_BUDGET_HEAP_FRAME));
+
+    let destination = match destination {
+        Some(destination) => destination,
+        None => {
+            let destination = spl_associated_token_account::get_associated_token_address(&signer, &token_mint_id);
+            if config.rpc_client.get_account(&destination).is_err() {
+                info!("No NEON token account {}; will be created", destination);
+                instructions.push(spl_associated_token_account::create_associated_token_account(
+                    &signer,
+                    &signer,
+                    &token_mint_id,
+                ));
+            }
+            destination
+        }
+    };
+
+    instructions.push(instruction_builder::operator_balance_withdraw(
+        &config.evm_loader,
+        &signer,
+        &destination,
+        amount,
+    ));
+
+    send_instructions(config, &instructions, config.rpc_client.commitment())?;
+
+    println!("{}", serde_json::json!({
+        "amount": amount,
+        "ether": hex::encode(balance_address),
+        "destination": destination.to_string(),
+    }));
+
+    Ok(())
+}
@@ -110,6 +110,15 @@ pub enum NeonCliError {
     // Account nonce exceeds u64::max
     #[error("Transaction count overflow")]
     TrxCountOverflow,
+    /// Account is not a valid SPL token mint
+    #[error("Invalid token mint {0:?}.")]
+    InvalidTokenMint(Pubkey),
+    /// Contract is not an ERC20 wrapper of the token mint
+    #[error("Contract {0:?} does not wrap token mint {1:?}.")]
+    TokenMintMismatch(H160, Pubkey),
+    /// Command sends dependent transactions and can't run with `--sign_only`
+    #[error("Command does not support sign-only mode.")]
+    SignOnlyNotSupported,
     /// Unknown Error.
     #[error("Unknown error.")]
     UnknownError
@@ -148,11 +157,96 @@ impl NeonCliError {
             NeonCliError::TransactionFailed                 => 244, // => 4200,
             NeonCliError::TooManySteps                      => 245,
             NeonCliError::TrxCountOverflow                  => 246,
+            NeonCliError::InvalidTokenMint(_)               => 247,
+            NeonCliError::TokenMintMismatch(_,_)            => 248,
             NeonCliError::UnknownError                      => 249, // => 4900,
+            NeonCliError::SignOnlyNotSupported              => 250,
         }
     }
 }
 
+impl NeonCliError {
+    pub fn category(&self) -> &'static str {
+        match self {
+            NeonCliError::StdIoError(_)                     => "io",
+            NeonCliError::ProgramError(_)                   => "program",
+            NeonCliError::ClientError(_)                    |
+            NeonCliError::TpuSenderError(_)                 => "solana_rpc",
+            NeonCliError::SignerError(_)                    |
+            NeonCliError::CliError(_)                       |
+            NeonCliError::EvmLoaderNotSpecified             |
+            NeonCliError::FeePayerNotSpecified              |
+            NeonCliError::InvalidVerbosityMessage           |
+            NeonCliError::SignOnlyNotSupported              => "config",
+            NeonCliError::AccountNotFound(_)                |
+            NeonCliError::AccountNotFoundAtAddress(_)       |
+            NeonCliError::CodeAccountNotFound(_)            |
+            NeonCliError::CodeAccountRequired(_)            |
+            NeonCliError::IncorrectAccount(_)               |
+            NeonCliError::AccountAlreadyExists(_)           |
+            NeonCliError::AccountAlreadyInitialized(_,_)    |
+            NeonCliError::ContractAccountExpected(_)        |
+            NeonCliError::DeploymentToExistingAccount(_)    |
+            NeonCliError::InvalidStorageAccountOwner(_)     |
+            NeonCliError::StorageAccountRequired(_)         |
+            NeonCliError::AccountIncorrectType(_)           |
+            NeonCliError::AccountDataTooSmall(_,_)          |
+            NeonCliError::AccountIsNotBpf(_)                |
+            NeonCliError::AccountIsNotUpgradeable(_)        |
+            NeonCliError::AssociatedPdaNotFound(_,_)        |
+            NeonCliError::InvalidAssociatedPda(_,_)         |
+            NeonCliError::InvalidTokenMint(_)               |
+            NeonCliError::TokenMintMismatch(_,_)            => "account",
+            NeonCliError::ConvertNonceError(_)              |
+            NeonCliError::TransactionFailed                 |
+            NeonCliError::TooManySteps                      |
+            NeonCliError::TrxCountOverflow                  => "transaction",
+            NeonCliError::UnknownError                      => "unknown",
+        }
+    }
+
+    /// Error is caused by node or network state and the same request may succeed later
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            NeonCliError::ClientError(e) => crate::rpc::is_transient(e),
+            NeonCliError::StdIoError(_) | NeonCliError::TpuSenderError(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Ethereum or Solana address the error refers to
+    pub fn account(&self) -> Option<String> {
+        match self {
+            NeonCliError::AccountNotFoundAtAddress(address)     |
+            NeonCliError::CodeAccountNotFound(address)          |
+            NeonCliError::CodeAccountRequired(address)          |
+            NeonCliError::IncorrectAccount(address)             |
+            NeonCliError::ContractAccountExpected(address)      |
+            NeonCliError::DeploymentToExistingAccount(address)  |
+            NeonCliError::TokenMintMismatch(address, _)         => Some(format!("0x{}", hex::encode(address.as_bytes()))),
+            NeonCliError::AccountNotFound(key)                  |
+            NeonCliError::AccountIsNotBpf(key)                  |
+            NeonCliError::AccountIsNotUpgradeable(key)          |
+            NeonCliError::InvalidStorageAccountOwner(key)       |
+            NeonCliError::InvalidTokenMint(key)                 |
+            NeonCliError::AccountAlreadyInitialized(key, _)     |
+            NeonCliError::AssociatedPdaNotFound(key, _)         |
+            NeonCliError::InvalidAssociatedPda(key, _)          => Some(key.to_string()),
+            _ => None,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "code": self.error_code(),
+            "category": self.category(),
+            "retryable": self.is_retryable(),
+            "account": self.account(),
+            "message": self.to_string(),
+        })
+    }
+}
+
 impl From<std::io::Error> for NeonCliError {
     fn from(e: std::io::Error) -> NeonCliError {
         NeonCliError::StdIoError(e)
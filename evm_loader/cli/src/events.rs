@@ -0,0 +1,97 @@
+use std::convert::TryInto;
+
+use evm::{H160, H256};
+use solana_sdk::pubkey::Pubkey;
+
+
+const PROGRAM_DATA: &str = "Program data: ";
+
+/// Event emitted by the Neon EVM program with `sol_log_data`
+#[derive(serde::Serialize, Debug, Clone)]
+#[serde(tag = "event")]
+pub enum NeonEvent {
+    /// `LOG0`..`LOG4`: Ethereum log record
+    Log {
+        address: H160,
+        topics: Vec<H256>,
+        #[serde(serialize_with = "serde_hex")]
+        data: Vec<u8>,
+    },
+    /// `RETURN`: transaction completed
+    Return {
+        exit_status: u8,
+        used_gas: u64,
+        #[serde(serialize_with = "serde_hex")]
+        result: Vec<u8>,
+    },
+}
+
+#[allow(clippy::ptr_arg)]
+fn serde_hex<S>(value: &Vec<u8>, s: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+    s.serialize_str(&hex::encode(value))
+}
+
+impl NeonEvent {
+    /// Decodes fields of a single `sol_log_data` record
+    #[must_use]
+    pub fn decode(fields: &[Vec<u8>]) -> Option<Self> {
+        let (mnemonic, fields) = fields.split_first()?;
+
+        match mnemonic.as_slice() {
+            b"RETURN" => match fields {
+                [exit_status, used_gas, result] => Some(Self::Return {
+                    exit_status: *exit_status.first()?,
+                    used_gas: u64::from_le_bytes(used_gas.as_slice().try_into().ok()?),
+                    result: result.clone(),
+                }),
+                _ => None,
+            },
+            [b'L', b'O', b'G', n @ b'0'..=b'4'] => {
+                // address | topics count | 4 topic slots (empty if absent) | data
+                if fields.len() != 7 {
+                    return None;
+                }
+
+                let address = H160::from_slice(fields[0].get(..20)?);
+                let count = usize::from(n - b'0');
+                let topics = fields[2..2 + count].iter()
+                    .map(|topic| (topic.len() == 32).then(|| H256::from_slice(topic)))
+                    .collect::<Option<Vec<_>>>()?;
+
+                Some(Self::Log { address, topics, data: fields[6].clone() })
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Extracts Neon EVM events from log messages of a confirmed Solana transaction.
+/// Records of other programs, including ones invoked by Neon EVM, are skipped.
+#[must_use]
+pub fn decode_log_messages(program_id: &Pubkey, log_messages: &[String]) -> Vec<NeonEvent> {
+    let invoke = format!("Program {} invoke", program_id);
+    let mut stack: Vec<bool> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in log_messages {
+        if line.starts_with("Program ") && line.contains(" invoke [") {
+            stack.push(line.starts_with(&invoke));
+        } else if line.starts_with("Program ") && (line.ends_with(" success") || line.contains(" failed: ")) {
+            stack.pop();
+        } else if let Some(data) = line.strip_prefix(PROGRAM_DATA) {
+            if stack.last() != Some(&true) {
+                continue;
+            }
+
+            let fields: Option<Vec<Vec<u8>>> = data.split(' ')
+                .map(|field| base64::decode(field).ok())
+                .collect();
+
+            if let Some(event) = fields.as_deref().and_then(NeonEvent::decode) {
+                events.push(event);
+            }
+        }
+    }
+
+    events
+}
@@ -17,25 +17,62 @@ impl LogContext {
 }
 
 
-const LOG_MODULES: [&str; 14] = [
+const LOG_MODULES: [&str; 30] = [
   "neon_cli",
   "neon_cli::account_storage",
   "neon_cli::commands::cancel_trx",
+  "neon_cli::commands::check_account_for_fee",
+  "neon_cli::commands::create_access_list",
+  "neon_cli::commands::create_lookup_table",
   "neon_cli::commands::create_ether_account",
   "neon_cli::commands::create_program_address",
   "neon_cli::commands::deploy",
   "neon_cli::commands::emulate",
+  "neon_cli::commands::emulate_solana_trx",
+  "neon_cli::commands::estimate_gas",
+  "neon_cli::commands::get_balance",
   "neon_cli::commands::get_ether_account_data",
+  "neon_cli::commands::get_create2_address",
+  "neon_cli::commands::get_erc20_binding",
+  "neon_cli::commands::get_holder",
+  "neon_cli::commands::get_neon_events",
+  "neon_cli::commands::get_neon_receipt",
+  "neon_cli::commands::get_neon_trx_timeline",
   "neon_cli::commands::get_neon_elf",
   "neon_cli::commands::get_storage_at",
+  "neon_cli::commands::list_stuck_states",
+  "neon_cli::commands::test_env",
   "neon_cli::commands::update_valids_table",
+  "neon_cli::commands::verify_holder",
+  "neon_cli::commands::withdraw_operator",
   "evm_loader::precompile",
   "evm_loader::executor",
   "evm_loader::external_programs",
 ];
 
 
-pub fn init(context: LogContext, loglevel: log::LevelFilter) -> Result<(), log::SetLoggerError> {
+#[derive(Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    Text,
+    Json,
+}
+
+impl LogFormat {
+    pub fn new(format: &str) -> LogFormat {
+        match format.to_ascii_lowercase().as_str() {
+            "json" => LogFormat::Json,
+            _      => LogFormat::Text,
+        }
+    }
+}
+
+
+pub fn init(
+    context: LogContext,
+    entity: &str,
+    loglevel: log::LevelFilter,
+    format: LogFormat,
+) -> Result<(), log::SetLoggerError> {
 
     let dispatch: Dispatch =
         if loglevel == log::LevelFilter::Off {
@@ -52,6 +89,8 @@ pub fn init(context: LogContext, loglevel: log::LevelFilter) -> Result<(), log::
             dispatch
         };
 
+    let entity = entity.to_string();
+
     dispatch
         .format(move |out, message, record| {
             let line: String = record.line().map_or("NA".to_string(), |v| v.to_string());
@@ -61,15 +100,33 @@ pub fn init(context: LogContext, loglevel: log::LevelFilter) -> Result<(), log::
                                         .and_then(OsStr::to_str)
                                         .unwrap_or("Undefined");
 
+            let datetime = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S%.3f");
+
+            if format == LogFormat::Json {
+                let js = serde_json::json!({
+                    "datetime": datetime.to_string(),
+                    "level": record.level().to_string(),
+                    "file": file_name,
+                    "line": line,
+                    "pid": process::id(),
+                    "component": "Emulator",
+                    "entity": entity,
+                    "context": context.req_id,
+                    "message": message.to_string(),
+                });
+                out.finish(format_args!("{}", js));
+                return;
+            }
+
             out.finish(format_args!(
                 "{datetime:23} {level:.1} {file:}:{lineno:} {pid:} {component:}:{entity:} {context:} {message:}",
-                datetime=chrono::Utc::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+                datetime=datetime,
                 level=record.level(),
                 file=file_name,
                 lineno=line,
                 pid=process::id(),
                 component="Emulator",
-                entity="Undefined",
+                entity=entity,
                 context=context.req_id,
                 message=message
             ));
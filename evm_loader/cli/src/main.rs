@@ -6,7 +6,15 @@ mod account_storage;
 mod syscall_stubs;
 
 mod errors;
+mod events;
 mod logs;
+mod metrics;
+mod operators;
+mod profiler;
+mod revert;
+mod rpc;
+mod session;
+mod transaction_signer;
 mod commands;
 
 use crate::{
@@ -15,17 +23,33 @@ use crate::{
         account_info,
     },
     commands::{
+        check_account_for_fee,
         emulate,
+        emulate_solana_trx,
+        estimate_gas,
+        create_access_list,
+        create_lookup_table,
         create_program_address,
         create_ether_account,
         deploy,
         deposit,
         migrate_account,
+        get_balance,
         get_ether_account_data,
+        get_create2_address,
+        get_erc20_binding,
+        get_holder,
+        get_neon_events,
+        get_neon_receipt,
+        get_neon_trx_timeline,
         cancel_trx,
+        list_stuck_states,
+        test_env,
         get_neon_elf,
         get_storage_at,
         update_valids_table,
+        verify_holder,
+        withdraw_operator,
     },
 };
 
@@ -39,13 +63,11 @@ use evm_loader::{
 
 use evm::{H160, H256, U256};
 use solana_sdk::{
-    commitment_config::{CommitmentConfig, CommitmentLevel},
+    commitment_config::{CommitmentConfig},
     instruction::{Instruction},
-    message::Message,
     pubkey::Pubkey,
     signature::{Keypair, Signer, Signature},
     keccak::Hasher,
-    transaction::Transaction,
     system_instruction,
 };
 use std::{
@@ -53,6 +75,7 @@ use std::{
     fs::File,
     env,
     str::FromStr,
+    path::Path,
     process::{exit},
     sync::Arc,
     convert::{TryInto},
@@ -67,23 +90,22 @@ use clap::{
 
 use solana_clap_utils::{
     input_parsers::{pubkey_of, value_of,},
-    input_validators::{is_url_or_moniker, is_valid_pubkey, normalize_to_url_if_moniker},
+    input_validators::{is_url_or_moniker, is_valid_pubkey, is_valid_signature, normalize_to_url_if_moniker},
     keypair::{signer_from_path, keypair_from_path},
 };
 
 use solana_client::{
     rpc_client::RpcClient,
-    rpc_config::{RpcSendTransactionConfig},
-    client_error::Result as SolanaClientResult,
 };
 
 use rlp::RlpStream;
 
 use log::{ debug, error};
-use logs::LogContext;
+use logs::{LogContext, LogFormat};
 
 use crate::errors::NeonCliError;
-use crate::get_neon_elf::CachedElfParams;
+use crate::revert::ErrorAbi;
+use crate::get_neon_elf::{CachedElfParams, token_mint_and_chain_id};
 
 type NeonCliResult = Result<(),NeonCliError>;
 
@@ -95,12 +117,15 @@ pub struct Config {
     // fee_payer: Pubkey,
     signer: Box<dyn Signer>,
     keypair: Option<Keypair>,
+    operators: Vec<Keypair>,
+    sign_only: bool,
     commitment: CommitmentConfig,
 }
 
 impl Debug for Config {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "evm_loader={:?}, signer={:?}", self.evm_loader, self.signer)
+        let operators: Vec<Pubkey> = self.operators.iter().map(Signer::pubkey).collect();
+        write!(f, "evm_loader={:?}, signer={:?}, operators={:?}", self.evm_loader, self.signer, operators)
     }
 }
 
@@ -218,7 +243,7 @@ fn get_program_ether(
     keccak256_h256(&stream.out()).into()
 }
 
-fn create_storage_account(config: &Config) -> SolanaClientResult<Pubkey> {
+fn create_storage_account(config: &Config) -> Result<Pubkey, NeonCliError> {
     use rand::Rng;
     let mut rng = rand::thread_rng();
     let creator = &config.signer;
@@ -245,7 +270,7 @@ fn create_account_with_seed(
     base: &Pubkey,
     seed: &str,
     len: u64
-) -> SolanaClientResult<Pubkey> {
+) -> Result<Pubkey, NeonCliError> {
     let created_account = Pubkey::create_with_seed(base, seed, &config.evm_loader).unwrap();
 
     if config.rpc_client.get_account_with_commitment(&created_account, CommitmentConfig::confirmed())?.value.is_none() {
@@ -268,36 +293,32 @@ fn create_account_with_seed(
     Ok(created_account)
 }
 
+/// Sends a transaction of `instructions` and waits for its confirmation
 fn send_transaction(
     config: &Config,
     instructions: &[Instruction]
-) -> SolanaClientResult<Signature> {
-    let message = Message::new(instructions, Some(&config.signer.pubkey()));
-    let mut transaction = Transaction::new_unsigned(message);
-    let signers = [&*config.signer];
-    let (blockhash, _last_valid_slot) = config.rpc_client
-        .get_latest_blockhash_with_commitment(CommitmentConfig::confirmed())?;
-    transaction.try_sign(&signers, blockhash)?;
-
-    config.rpc_client.send_and_confirm_transaction_with_spinner_and_config(
-        &transaction,
-        CommitmentConfig::confirmed(),
-        RpcSendTransactionConfig {
-            preflight_commitment: Some(CommitmentLevel::Confirmed),
-            ..RpcSendTransactionConfig::default()
-        },
-    )
-
-    // Ok(tx_sig)
+) -> Result<Signature, NeonCliError> {
+    if config.sign_only {
+        return Err(NeonCliError::SignOnlyNotSupported);
+    }
+
+    transaction_signer::send_instructions(config, instructions, CommitmentConfig::confirmed())?
+        .ok_or(NeonCliError::SignOnlyNotSupported)
 }
 
 /// Returns random nonce and the corresponding seed.
 fn generate_random_holder_seed() -> (u64, String) {
     use rand::Rng as _;
-    // proxy_id_bytes = proxy_id.to_bytes((proxy_id.bit_length() + 7) // 8, 'big')
-    // seed = keccak_256(b'holder' + proxy_id_bytes).hexdigest()[:32]
     let mut rng = rand::thread_rng();
     let id: u64 = rng.gen();
+    (id, holder_seed(id))
+}
+
+/// Seed of the holder account created by the operator for the holder id
+#[must_use]
+pub fn holder_seed(id: u64) -> String {
+    // proxy_id_bytes = proxy_id.to_bytes((proxy_id.bit_length() + 7) // 8, 'big')
+    // seed = keccak_256(b'holder' + proxy_id_bytes).hexdigest()[:32]
     let bytes_count = std::mem::size_of_val(&id);
     let bits_count = bytes_count * 8;
     let holder_id_bit_length = bits_count - id.leading_zeros() as usize;
@@ -306,7 +327,7 @@ fn generate_random_holder_seed() -> (u64, String) {
     hasher.hash(b"holder");
     hasher.hash(&id.to_be_bytes()[bytes_count-significant_bytes_count..]);
     let output = hasher.result();
-    (id, hex::encode(output)[..32].into())
+    hex::encode(output)[..32].into()
 }
 
 fn make_clean_hex(in_str: &str) -> &str {
@@ -344,6 +365,26 @@ fn h160_of(matches: &ArgMatches<'_>, name: &str) -> Option<H160> {
     })
 }
 
+// Return H256 for an argument
+fn h256_of(matches: &ArgMatches<'_>, name: &str) -> Option<H256> {
+    matches.value_of(name).map(|value| {
+        H256::from_str(make_clean_hex(value)).unwrap()
+    })
+}
+
+// Return an error if string cannot be parsed as a H256 hash
+fn is_valid_h256<T>(string: T) -> Result<(), String> where T: AsRef<str>,
+{
+    H256::from_str(make_clean_hex(string.as_ref())).map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+// Return an error if string is not a valid custom error signature
+fn is_valid_error_abi<T>(string: T) -> Result<(), String> where T: AsRef<str>,
+{
+    string.as_ref().parse::<ErrorAbi>().map(|_| ())
+}
+
 // Return U256 for an argument
 fn u256_of(matches: &ArgMatches<'_>, name: &str) -> Option<U256> {
     matches.value_of(name).map(|value| {
@@ -365,6 +406,30 @@ fn is_valid_u256<T>(string: T) -> Result<(), String> where T: AsRef<str>,
         .map_err(|e| e.to_string())
 }
 
+// Parse ADDRESS:INDEX storage slot
+fn parse_storage_slot(value: &str) -> Result<(H160, U256), String> {
+    let (address, index) = value.split_once(':')
+        .ok_or_else(|| format!("Expected ADDRESS:INDEX, provided: {}", value))?;
+
+    let address = H160::from_str(make_clean_hex(address)).map_err(|e| e.to_string())?;
+    let index = U256::from_str(make_clean_hex(index)).map_err(|e| e.to_string())?;
+
+    Ok((address, index))
+}
+
+// Return an error if string cannot be parsed as ADDRESS:INDEX pair
+fn is_valid_storage_slot<T>(string: T) -> Result<(), String> where T: AsRef<str>,
+{
+    parse_storage_slot(string.as_ref()).map(|_| ())
+}
+
+// Return (address, index) pairs for an argument
+fn storage_slots_of(matches: &ArgMatches<'_>, name: &str) -> Option<Vec<(H160, U256)>> {
+    matches.values_of(name).map(|values| {
+        values.map(|value| parse_storage_slot(value).unwrap()).collect()
+    })
+}
+
 // Return hexdata for an argument
 fn hexdata_of(matches: &ArgMatches<'_>, name: &str) -> Option<Vec<u8>> {
     matches.value_of(name).and_then(|value| {
@@ -501,9 +566,248 @@ fn main() {
                 .global(true)
                 .help("Logging level"),
         )
+        .arg(
+            Arg::with_name("operator_keypair")
+                .long("operator_keypair")
+                .value_name("KEYPAIR_PATH")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .global(true)
+                .help("Operator keypair used as fee payer, may be repeated. \
+                       The first one with sufficient balance is used, the default signer is the last resort"),
+        )
+        .arg(
+            Arg::with_name("sign_only")
+                .long("sign_only")
+                .takes_value(false)
+                .global(true)
+                .help("Sign transactions with available keys and print them for external co-signing instead of sending. \
+                       Not supported by deploy"),
+        )
+        .arg(
+            Arg::with_name("metrics")
+                .long("metrics")
+                .takes_value(false)
+                .global(true)
+                .help("Print command metrics to stderr on exit"),
+        )
+        .arg(
+            Arg::with_name("logformat")
+                .long("logformat")
+                .value_name("LOG_FORMAT")
+                .takes_value(true)
+                .global(true)
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .help("Logging output format"),
+        )
         .subcommand(
             SubCommand::with_name("emulate")
                 .about("Emulate execution of Ethereum transaction")
+                .arg(
+                    Arg::with_name("sender")
+                        .value_name("SENDER")
+                        .takes_value(true)
+                        .index(1)
+                        .required_unless("raw_transaction")
+                        .validator(is_valid_h160)
+                        .help("The sender of the transaction")
+                )
+                .arg(
+                    Arg::with_name("contract")
+                        .value_name("CONTRACT")
+                        .takes_value(true)
+                        .index(2)
+                        .required_unless("raw_transaction")
+                        .validator(is_valid_h160_or_deploy)
+                        .help("The contract that executes the transaction or 'deploy'")
+                )
+                .arg(
+                    Arg::with_name("data")
+                        .value_name("DATA")
+                        .takes_value(true)
+                        .index(3)
+                        .required(false)
+                        .validator(is_valid_hexdata)
+                        .help("Transaction data or 'None'")
+                )
+                .arg(
+                    Arg::with_name("value")
+                        .value_name("VALUE")
+                        .takes_value(true)
+                        .index(4)
+                        .required(false)
+                        .validator(is_amount::<U256, _>)
+                        .help("Transaction value")
+                )
+                .arg(
+                    Arg::with_name("raw_transaction")
+                        .long("raw_transaction")
+                        .value_name("RAW_TRANSACTION")
+                        .takes_value(true)
+                        .conflicts_with_all(&["sender", "contract", "data", "value"])
+                        .validator(is_valid_hexdata)
+                        .help("Signed transaction in RLP: sender is recovered, gas limit, value and data are taken from it")
+                )
+                .arg(
+                    Arg::with_name("token_mint")
+                        .long("token_mint")
+                        .value_name("TOKEN_MINT")
+                        .takes_value(true)
+                        .global(true)
+                        .validator(is_valid_pubkey)
+                        .help("Pubkey for token_mint")
+                )
+                .arg(
+                    Arg::with_name("chain_id")
+                        .long("chain_id")
+                        .value_name("CHAIN_ID")
+                        .takes_value(true)
+                        .required(false)
+                        .help("Network chain_id"),
+                )
+                .arg(
+                    Arg::with_name("max_steps_to_execute")
+                        .long("max_steps_to_execute")
+                        .value_name("NUMBER_OF_STEPS")
+                        .takes_value(true)
+                        .required(false)
+                        .default_value("100000")
+                        .help("Maximal number of steps to execute in a single run"),
+                )
+                .arg(
+                    Arg::with_name("read_only")
+                        .long("read_only")
+                        .takes_value(false)
+                        .required(false)
+                        .help("Read-only call (eth_call): skip sender nonce and balance checks and rent bookkeeping, \
+                               writable accounts and used gas are not accurate. Ignored for 'deploy'"),
+                )
+                .arg(
+                    Arg::with_name("errors")
+                        .long("errors")
+                        .value_name("ERROR_SIGNATURE")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(false)
+                        .validator(is_valid_error_abi)
+                        .help("Custom error signatures to decode revert data, e.g. 'InsufficientBalance(uint256,uint256)'"),
+                )
+                .arg(
+                    Arg::with_name("session")
+                        .long("session")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(false)
+                        .help("Emulate on top of the state saved in FILE and save the state changed by a successful emulation"),
+                )
+                .arg(
+                    Arg::with_name("profile")
+                        .long("profile")
+                        .takes_value(false)
+                        .required(false)
+                        .help("Add executed count and used gas per opcode and per contract to the output"),
+                )
+                .arg(
+                    Arg::with_name("lookup_table")
+                        .long("lookup_table")
+                        .takes_value(false)
+                        .required(false)
+                        .help("Split used Solana accounts into static keys and address lookup table contents"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("create-lookup-table")
+                .about("Create or extend address lookup table with Solana accounts of emulated transaction")
+                .arg(
+                    Arg::with_name("addresses")
+                        .index(1)
+                        .value_name("ADDRESS")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .help("Solana accounts to add"),
+                )
+                .arg(
+                    Arg::with_name("lookup_table")
+                        .long("lookup_table")
+                        .value_name("LOOKUP_TABLE")
+                        .takes_value(true)
+                        .required(false)
+                        .validator(is_valid_pubkey)
+                        .help("Existing lookup table of the signer to extend instead of creating a new one"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("estimate-gas")
+                .about("Estimate gas limit of Ethereum transaction, including iterative execution overhead")
+                .arg(
+                    Arg::with_name("sender")
+                        .value_name("SENDER")
+                        .takes_value(true)
+                        .index(1)
+                        .required(true)
+                        .validator(is_valid_h160)
+                        .help("The sender of the transaction")
+                )
+                .arg(
+                    Arg::with_name("contract")
+                        .value_name("CONTRACT")
+                        .takes_value(true)
+                        .index(2)
+                        .required(true)
+                        .validator(is_valid_h160_or_deploy)
+                        .help("The contract that executes the transaction or 'deploy'")
+                )
+                .arg(
+                    Arg::with_name("data")
+                        .value_name("DATA")
+                        .takes_value(true)
+                        .index(3)
+                        .required(false)
+                        .validator(is_valid_hexdata)
+                        .help("Transaction data or 'None'")
+                )
+                .arg(
+                    Arg::with_name("value")
+                        .value_name("VALUE")
+                        .takes_value(true)
+                        .index(4)
+                        .required(false)
+                        .validator(is_amount::<U256, _>)
+                        .help("Transaction value")
+                )
+                .arg(
+                    Arg::with_name("token_mint")
+                        .long("token_mint")
+                        .value_name("TOKEN_MINT")
+                        .takes_value(true)
+                        .validator(is_valid_pubkey)
+                        .help("Pubkey for token_mint")
+                )
+                .arg(
+                    Arg::with_name("chain_id")
+                        .long("chain_id")
+                        .value_name("CHAIN_ID")
+                        .takes_value(true)
+                        .required(false)
+                        .help("Network chain_id"),
+                )
+                .arg(
+                    Arg::with_name("max_steps_to_execute")
+                        .long("max_steps_to_execute")
+                        .value_name("NUMBER_OF_STEPS")
+                        .takes_value(true)
+                        .required(false)
+                        .default_value("100000")
+                        .help("Maximal number of steps to execute in a single run"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("create-access-list")
+                .about("Create EIP-2930 access list of Ethereum transaction and estimate its gas limit")
                 .arg(
                     Arg::with_name("sender")
                         .value_name("SENDER")
@@ -545,7 +849,6 @@ fn main() {
                         .long("token_mint")
                         .value_name("TOKEN_MINT")
                         .takes_value(true)
-                        .global(true)
                         .validator(is_valid_pubkey)
                         .help("Pubkey for token_mint")
                 )
@@ -643,6 +946,28 @@ fn main() {
                         .help("Ethereum address"),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("withdraw-operator")
+                .about("Withdraw NEONs earned by the signer operator from its balance ether account")
+                .arg(
+                    Arg::with_name("amount")
+                        .index(1)
+                        .value_name("AMOUNT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_amount::<u64, _>)
+                        .help("Amount to withdraw"),
+                )
+                .arg(
+                    Arg::with_name("destination")
+                        .long("destination")
+                        .value_name("DESTINATION")
+                        .takes_value(true)
+                        .required(false)
+                        .validator(is_valid_pubkey)
+                        .help("NEON token account to withdraw to (default: associated token account of the signer)"),
+                )
+        )
         .subcommand(
             SubCommand::with_name("migrate-account")
                 .about("Migrates account internal structure to v2")
@@ -656,6 +981,20 @@ fn main() {
                         .help("Ethereum address"),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("get-balance")
+                .about("Get balances of Ethereum accounts, prints JSON array")
+                .arg(
+                    Arg::with_name("ether")
+                        .index(1)
+                        .value_name("ether")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(true)
+                        .validator(is_valid_h160)
+                        .help("Ethereum addresses"),
+                )
+        )
         .subcommand(
             SubCommand::with_name("get-ether-account-data")
                 .about("Get values stored in associated with given address account data")
@@ -669,6 +1008,288 @@ fn main() {
                         .help("Ethereum address"),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("get-erc20-binding")
+                .about("Get ERC20-for-SPL binding descriptor for given token mint and wrapper contract")
+                .arg(
+                    Arg::with_name("token_mint")
+                        .index(1)
+                        .value_name("TOKEN_MINT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .help("SPL token mint"),
+                )
+                .arg(
+                    Arg::with_name("contract")
+                        .index(2)
+                        .value_name("CONTRACT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_h160)
+                        .help("ERC20 wrapper contract address"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("get-holder")
+                .about("Get decoded content of holder or storage account")
+                .arg(
+                    Arg::with_name("holder")
+                        .index(1)
+                        .value_name("HOLDER_ACCOUNT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .help("Holder or storage account"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("list-holders")
+                .about("List holder and storage accounts of the operator")
+                .arg(
+                    Arg::with_name("operator")
+                        .index(1)
+                        .value_name("OPERATOR")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .help("Operator public key"),
+                )
+                .arg(
+                    Arg::with_name("max_id")
+                        .long("max_id")
+                        .value_name("MAX_ID")
+                        .takes_value(true)
+                        .required(false)
+                        .validator(is_amount::<u64, _>)
+                        .help("Number of holder ids to check (default 256)"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("get-create2-address")
+                .about("Compute CREATE2 contract address and check the target Neon account")
+                .arg(
+                    Arg::with_name("deployer")
+                        .index(1)
+                        .value_name("DEPLOYER")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_h160)
+                        .help("Deployer contract address"),
+                )
+                .arg(
+                    Arg::with_name("salt")
+                        .index(2)
+                        .value_name("SALT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_h256)
+                        .help("CREATE2 salt"),
+                )
+                .arg(
+                    Arg::with_name("init_code_hash")
+                        .index(3)
+                        .value_name("INIT_CODE_HASH")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_h256)
+                        .help("Keccak256 hash of the contract init code"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("check-account-for-fee")
+                .about("Print balances of operator keypairs and default signer used to pay fees")
+        )
+        .subcommand(
+            SubCommand::with_name("get-neon-events")
+                .about("Decode Neon EVM events from logs of a confirmed Solana transaction")
+                .arg(
+                    Arg::with_name("signature")
+                        .index(1)
+                        .value_name("SIGNATURE")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_signature)
+                        .help("Solana transaction signature"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("get-neon-trx-timeline")
+                .about("Find Solana transactions executing NEON transaction by its hash")
+                .arg(
+                    Arg::with_name("trx_hash")
+                        .index(1)
+                        .value_name("TRX_HASH")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_h256)
+                        .help("Ethereum transaction hash"),
+                )
+                .arg(
+                    Arg::with_name("sender")
+                        .index(2)
+                        .value_name("SENDER")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_h160)
+                        .help("Ethereum transaction sender"),
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .value_name("LIMIT")
+                        .takes_value(true)
+                        .required(false)
+                        .validator(is_amount::<usize, _>)
+                        .help("Number of latest sender and holder signatures to scan (default 1000)"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("get-neon-receipt")
+                .about("Get receipt of NEON transaction from logs of Solana transactions executing it")
+                .arg(
+                    Arg::with_name("trx_hash")
+                        .index(1)
+                        .value_name("TRX_HASH")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_h256)
+                        .help("Ethereum transaction hash"),
+                )
+                .arg(
+                    Arg::with_name("sender")
+                        .index(2)
+                        .value_name("SENDER")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_h160)
+                        .help("Ethereum transaction sender"),
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .value_name("LIMIT")
+                        .takes_value(true)
+                        .required(false)
+                        .validator(is_amount::<usize, _>)
+                        .help("Number of latest sender and holder signatures to scan (default 1000)"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("emulate-solana-trx")
+                .about("Emulate NEON transaction executed by a confirmed Solana transaction against the current state")
+                .arg(
+                    Arg::with_name("signature")
+                        .index(1)
+                        .value_name("SIGNATURE")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_signature)
+                        .help("Solana transaction signature"),
+                )
+                .arg(
+                    Arg::with_name("max_steps_to_execute")
+                        .long("max_steps_to_execute")
+                        .value_name("NUMBER_OF_STEPS")
+                        .takes_value(true)
+                        .required(false)
+                        .default_value("100000")
+                        .help("Maximal number of steps to execute in a single run"),
+                )
+                .arg(
+                    Arg::with_name("errors")
+                        .long("errors")
+                        .value_name("ERROR_SIGNATURE")
+                        .takes_value(true)
+                        .multiple(true)
+                        .required(false)
+                        .validator(is_valid_error_abi)
+                        .help("Custom error signatures to decode revert data, e.g. 'InsufficientBalance(uint256,uint256)'"),
+                )
+                .arg(
+                    Arg::with_name("limit")
+                        .long("limit")
+                        .value_name("LIMIT")
+                        .takes_value(true)
+                        .required(false)
+                        .validator(is_amount::<usize, _>)
+                        .help("Number of holder signatures to scan to restore the holder (default 1000)"),
+                )
+                .arg(
+                    Arg::with_name("profile")
+                        .long("profile")
+                        .takes_value(false)
+                        .required(false)
+                        .help("Add executed count and used gas per opcode and per contract to the output"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("list-stuck-states")
+                .about("List unfinished NEON transactions of all operators")
+                .arg(
+                    Arg::with_name("min_age")
+                        .long("min_age")
+                        .value_name("SLOTS")
+                        .takes_value(true)
+                        .required(false)
+                        .validator(is_amount::<u64, _>)
+                        .help("Skip states updated less than SLOTS ago"),
+                )
+                .arg(
+                    Arg::with_name("cancel")
+                        .long("cancel")
+                        .takes_value(false)
+                        .help("Add unsigned cancel transaction for each state, the default signer pays fees"),
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("test-env")
+                .about("Local Solana validator with deployed Neon EVM for contract development")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .arg(
+                    Arg::with_name("ledger")
+                        .long("ledger")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .global(true)
+                        .default_value("test-ledger")
+                        .help("Ledger directory of the test validator"),
+                )
+                .subcommand(
+                    SubCommand::with_name("up")
+                        .about("Start solana-test-validator with Neon EVM in genesis, deploy tokens and fund the signer as operator")
+                        .arg(
+                            Arg::with_name("scripts_dir")
+                                .long("scripts_dir")
+                                .value_name("DIR")
+                                .takes_value(true)
+                                .default_value(".")
+                                .help("Directory with evm_loader.so, deploy-evm.sh and token keypairs"),
+                        )
+                        .arg(
+                            Arg::with_name("rpc_port")
+                                .long("rpc_port")
+                                .value_name("PORT")
+                                .takes_value(true)
+                                .default_value("8899")
+                                .validator(is_amount::<u16, _>)
+                                .help("JSON RPC port of the test validator, websocket uses the next one"),
+                        )
+                        .arg(
+                            Arg::with_name("airdrop")
+                                .long("airdrop")
+                                .value_name("SOL")
+                                .takes_value(true)
+                                .default_value("1000")
+                                .validator(is_amount::<f64, _>)
+                                .help("Amount of SOL for the operator"),
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("down")
+                        .about("Stop solana-test-validator started by test-env up")
+                )
+        )
         .subcommand(
             SubCommand::with_name("cancel-trx")
                 .about("Cancel NEON transaction")
@@ -693,6 +1314,13 @@ fn main() {
                         .required(false)
                         .help("/path/to/evm_loader.so"),
                 )
+                .arg(
+                    Arg::with_name("diff")
+                        .long("diff")
+                        .takes_value(false)
+                        .requires("program_location")
+                        .help("Compare params of the program file with the deployed program"),
+                )
         )
         .subcommand(
             SubCommand::with_name("get-storage-at")
@@ -703,7 +1331,7 @@ fn main() {
                         .value_name("contract_id")
                         .takes_value(true)
                         .validator(is_valid_h160)
-                        .required(true),
+                        .required_unless("slots"),
                 )
                 .arg(
                     Arg::with_name("index")
@@ -711,7 +1339,17 @@ fn main() {
                         .value_name("index")
                         .takes_value(true)
                         .validator(is_valid_u256)
-                        .required(true),
+                        .required_unless("slots"),
+                )
+                .arg(
+                    Arg::with_name("slots")
+                        .long("slots")
+                        .value_name("ADDRESS:INDEX")
+                        .takes_value(true)
+                        .multiple(true)
+                        .conflicts_with_all(&["contract_id", "index"])
+                        .validator(is_valid_storage_slot)
+                        .help("Read several storage values at once, prints JSON array"),
                 )
         )
         .subcommand(
@@ -726,6 +1364,37 @@ fn main() {
                         .required(true),
                 )
         )
+        .subcommand(
+            SubCommand::with_name("verify-holder")
+                .about("Verify holder account content against transaction hash")
+                .arg(
+                    Arg::with_name("holder")
+                        .index(1)
+                        .value_name("HOLDER_ACCOUNT")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_pubkey)
+                        .help("Holder account"),
+                )
+                .arg(
+                    Arg::with_name("trx_hash")
+                        .index(2)
+                        .value_name("TRX_HASH")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(is_valid_h256)
+                        .help("Expected Ethereum transaction hash"),
+                )
+                .arg(
+                    Arg::with_name("chunk_size")
+                        .long("chunk_size")
+                        .value_name("CHUNK_SIZE")
+                        .takes_value(true)
+                        .required(false)
+                        .validator(is_amount::<u64, _>)
+                        .help("Size of holder write chunks"),
+                )
+        )
         .get_matches();
 
     let context: LogContext =
@@ -744,7 +1413,9 @@ fn main() {
                     _       => log::LevelFilter::Trace,
                 }
             );
-    logs::init(context, loglevel).unwrap();
+    let logformat = LogFormat::new(app_matches.value_of("logformat").unwrap());
+    let entity = app_matches.subcommand_name().unwrap_or("Undefined");
+    logs::init(context, entity, loglevel, logformat).unwrap();
 
     let mut wallet_manager = None;
     let config = {
@@ -799,12 +1470,21 @@ fn main() {
             true,
         ).ok();
 
+        let operators = app_matches.values_of("operator_keypair")
+            .map_or_else(|| Ok(Vec::new()), operators::read_keypairs)
+            .unwrap_or_else(|e| {
+                error!("{}", e);
+                exit(e.error_code() as i32);
+            });
+
         Config {
             rpc_client: Arc::new(RpcClient::new_with_commitment(json_rpc_url, commitment)),
             websocket_url: "".to_string(),
             evm_loader,
             signer,
             keypair,
+            operators,
+            sign_only: app_matches.is_present("sign_only"),
             commitment,
         }
     };
@@ -813,35 +1493,87 @@ fn main() {
     let result: NeonCliResult =
         match (sub_command, sub_matches) {
             ("emulate", Some(arg_matches)) => {
+                let (token_mint, chain_id) = token_mint_and_chain_id(
+                    &config,
+                    pubkey_of(arg_matches, "token_mint"),
+                    value_of(arg_matches, "chain_id"),
+                );
+                let max_steps_to_execute = value_of::<u64>(arg_matches, "max_steps_to_execute").unwrap();
+                let read_only = arg_matches.is_present("read_only");
+                let errors: Vec<ErrorAbi> = arg_matches.values_of("errors")
+                    .map(|values| values.map(|value| value.parse().unwrap()).collect())
+                    .unwrap_or_default();
+                let session = arg_matches.value_of("session").map(Path::new);
+                let profile = arg_matches.is_present("profile");
+                let lookup_table = arg_matches.is_present("lookup_table");
+
+                hexdata_of(arg_matches, "raw_transaction")
+                    .map(|raw| emulate::decode_raw_transaction(&raw, chain_id))
+                    .transpose()
+                    .and_then(|raw_transaction| {
+                        let (contract, sender, data, value, gas_limit) = match raw_transaction {
+                            Some((sender, trx)) => (trx.to, sender, Some(trx.call_data), Some(trx.value), Some(trx.gas_limit)),
+                            None => (
+                                h160_or_deploy_of(arg_matches, "contract"),
+                                h160_of(arg_matches, "sender").unwrap(),
+                                hexdata_of(arg_matches, "data"),
+                                value_of(arg_matches, "value"),
+                                None,
+                            ),
+                        };
+
+                        emulate::execute(&config,
+                                         contract,
+                                         sender,
+                                         data,
+                                         value,
+                                         gas_limit,
+                                         &token_mint,
+                                         chain_id,
+                                         max_steps_to_execute,
+                                         read_only,
+                                         &errors,
+                                         session,
+                                         profile,
+                                         lookup_table)
+                    })
+            }
+            ("create-lookup-table", Some(arg_matches)) => {
+                let addresses: Vec<Pubkey> = arg_matches.values_of("addresses").unwrap()
+                    .map(|value| Pubkey::from_str(value).unwrap())
+                    .collect();
+                let lookup_table = pubkey_of(arg_matches, "lookup_table");
+                create_lookup_table::execute(&config, lookup_table, &addresses)
+            }
+            ("estimate-gas", Some(arg_matches)) => {
                 let contract = h160_or_deploy_of(arg_matches, "contract");
                 let sender = h160_of(arg_matches, "sender").unwrap();
                 let data = hexdata_of(arg_matches, "data");
                 let value = value_of(arg_matches, "value");
 
-                // Read ELF params only if token_mint or chain_id is not set.
-                let mut token_mint = pubkey_of(arg_matches, "token_mint");
-                let mut chain_id = value_of(arg_matches, "chain_id");
-                if token_mint.is_none() || chain_id.is_none() {
-                    let cached_elf_params = CachedElfParams::new(&config);
-                    token_mint = token_mint.or_else(|| Some(Pubkey::from_str(
-                        cached_elf_params.get("NEON_TOKEN_MINT").unwrap()
-                    ).unwrap()));
-                    chain_id = chain_id.or_else(|| Some(u64::from_str(
-                        cached_elf_params.get("NEON_CHAIN_ID").unwrap()
-                    ).unwrap()));
-                }
-                let token_mint = token_mint.unwrap();
-                let chain_id = chain_id.unwrap();
+                let (token_mint, chain_id) = token_mint_and_chain_id(
+                    &config,
+                    pubkey_of(arg_matches, "token_mint"),
+                    value_of(arg_matches, "chain_id"),
+                );
+                let max_steps_to_execute = value_of::<u64>(arg_matches, "max_steps_to_execute").unwrap();
+
+                estimate_gas::execute(&config, contract, sender, data, value, &token_mint, chain_id, max_steps_to_execute)
+            }
+            ("create-access-list", Some(arg_matches)) => {
+                let contract = h160_or_deploy_of(arg_matches, "contract");
+                let sender = h160_of(arg_matches, "sender").unwrap();
+                let data = hexdata_of(arg_matches, "data");
+                let value = value_of(arg_matches, "value");
+
+                let (token_mint, chain_id) = token_mint_and_chain_id(
+                    &config,
+                    pubkey_of(arg_matches, "token_mint"),
+                    value_of(arg_matches, "chain_id"),
+                );
                 let max_steps_to_execute = value_of::<u64>(arg_matches, "max_steps_to_execute").unwrap();
 
-                emulate::execute(&config,
-                                 contract,
-                                 sender,
-                                 data,
-                                 value,
-                                 &token_mint,
-                                 chain_id,
-                                 max_steps_to_execute)
+                create_access_list::execute(&config, contract, sender, data, value, &token_mint, chain_id, max_steps_to_execute)
             }
             ("create-program-address", Some(arg_matches)) => {
                 let ether = h160_of(arg_matches, "seed").unwrap();
@@ -877,42 +1609,140 @@ fn main() {
                 let ether = h160_of(arg_matches, "ether").unwrap();
                 deposit::execute(&config, amount, &ether)
             }
+            ("withdraw-operator", Some(arg_matches)) => {
+                let amount = value_of(arg_matches, "amount").unwrap();
+                let destination = pubkey_of(arg_matches, "destination");
+                withdraw_operator::execute(&config, amount, destination)
+            }
             ("migrate-account", Some(arg_matches)) => {
                 let ether = h160_of(arg_matches, "ether").unwrap();
                 migrate_account::execute(&config, &ether)
             }
+            ("get-balance", Some(arg_matches)) => {
+                let addresses: Vec<H160> = arg_matches.values_of("ether").unwrap()
+                    .map(|value| H160::from_str(make_clean_hex(value)).unwrap())
+                    .collect();
+                get_balance::execute(&config, &addresses)
+            }
             ("get-ether-account-data", Some(arg_matches)) => {
                 let ether = h160_of(arg_matches, "ether").unwrap();
                 get_ether_account_data::execute(&config, &ether);
                 Ok(())
             }
+            ("get-erc20-binding", Some(arg_matches)) => {
+                let token_mint = pubkey_of(arg_matches, "token_mint").unwrap();
+                let contract = h160_of(arg_matches, "contract").unwrap();
+                get_erc20_binding::execute(&config, &token_mint, &contract)
+            }
+            ("get-holder", Some(arg_matches)) => {
+                let holder = pubkey_of(arg_matches, "holder").unwrap();
+                get_holder::execute(&config, &holder)
+            }
+            ("list-holders", Some(arg_matches)) => {
+                let operator = pubkey_of(arg_matches, "operator").unwrap();
+                let max_id = value_of(arg_matches, "max_id").unwrap_or(256);
+                get_holder::list(&config, &operator, max_id)
+            }
+            ("get-create2-address", Some(arg_matches)) => {
+                let deployer = h160_of(arg_matches, "deployer").unwrap();
+                let salt = h256_of(arg_matches, "salt").unwrap();
+                let init_code_hash = h256_of(arg_matches, "init_code_hash").unwrap();
+                get_create2_address::execute(&config, &deployer, &salt, &init_code_hash)
+            }
+            ("check-account-for-fee", Some(_)) => {
+                check_account_for_fee::execute(&config)
+            }
+            ("get-neon-events", Some(arg_matches)) => {
+                let signature = value_of(arg_matches, "signature").unwrap();
+                get_neon_events::execute(&config, &signature)
+            }
+            ("get-neon-trx-timeline", Some(arg_matches)) => {
+                let trx_hash = h256_of(arg_matches, "trx_hash").unwrap();
+                let sender = h160_of(arg_matches, "sender").unwrap();
+                let limit = value_of(arg_matches, "limit").unwrap_or(1000);
+                get_neon_trx_timeline::execute(&config, &trx_hash, &sender, limit)
+            }
+            ("get-neon-receipt", Some(arg_matches)) => {
+                let trx_hash = h256_of(arg_matches, "trx_hash").unwrap();
+                let sender = h160_of(arg_matches, "sender").unwrap();
+                let limit = value_of(arg_matches, "limit").unwrap_or(1000);
+                get_neon_receipt::execute(&config, &trx_hash, &sender, limit)
+            }
+            ("emulate-solana-trx", Some(arg_matches)) => {
+                let signature = value_of(arg_matches, "signature").unwrap();
+                let max_steps_to_execute = value_of::<u64>(arg_matches, "max_steps_to_execute").unwrap();
+                let errors: Vec<ErrorAbi> = arg_matches.values_of("errors")
+                    .map(|values| values.map(|value| value.parse().unwrap()).collect())
+                    .unwrap_or_default();
+                let limit = value_of(arg_matches, "limit").unwrap_or(1000);
+                let profile = arg_matches.is_present("profile");
+
+                let (token_mint, chain_id) = token_mint_and_chain_id(&config, None, None);
+
+                emulate_solana_trx::execute(&config, &signature, &token_mint, chain_id, max_steps_to_execute, &errors, limit, profile)
+            }
+            ("list-stuck-states", Some(arg_matches)) => {
+                let min_age = value_of(arg_matches, "min_age").unwrap_or(0);
+                let with_cancel = arg_matches.is_present("cancel");
+                list_stuck_states::execute(&config, min_age, with_cancel)
+            }
+            ("test-env", Some(arg_matches)) => {
+                let ledger = Path::new(arg_matches.value_of("ledger").unwrap());
+                match arg_matches.subcommand() {
+                    ("up", Some(up_matches)) => {
+                        let scripts_dir = Path::new(up_matches.value_of("scripts_dir").unwrap());
+                        let rpc_port = value_of(up_matches, "rpc_port").unwrap();
+                        let airdrop = value_of(up_matches, "airdrop").unwrap();
+                        test_env::up(&config, ledger, scripts_dir, rpc_port, airdrop)
+                    }
+                    ("down", Some(_)) => {
+                        test_env::down(&config, ledger)
+                    }
+                    _ => unreachable!(),
+                }
+            }
             ("cancel-trx", Some(arg_matches)) => {
                 let storage_account = pubkey_of(arg_matches, "storage_account").unwrap();
                 cancel_trx::execute(&config, &storage_account)
             }
             ("neon-elf-params", Some(arg_matches)) => {
                 let program_location = arg_matches.value_of("program_location");
-                get_neon_elf::execute(&config, program_location)
+                let diff = arg_matches.is_present("diff");
+                get_neon_elf::execute(&config, program_location, diff)
             }
             ("get-storage-at", Some(arg_matches)) => {
-                let contract_id = h160_of(arg_matches, "contract_id").unwrap();
-                let index = u256_of(arg_matches, "index").unwrap();
-                get_storage_at::execute(&config, contract_id, &index);
-                Ok(())
+                if let Some(slots) = storage_slots_of(arg_matches, "slots") {
+                    get_storage_at::execute_batch(&config, &slots)
+                } else {
+                    let contract_id = h160_of(arg_matches, "contract_id").unwrap();
+                    let index = u256_of(arg_matches, "index").unwrap();
+                    get_storage_at::execute(&config, contract_id, &index)
+                }
             }
             ("update-valids-table", Some(arg_matches)) => {
                 let contract_id = h160_of(arg_matches, "contract_id").unwrap();
                 update_valids_table::execute(&config, contract_id)
             }
+            ("verify-holder", Some(arg_matches)) => {
+                let holder = pubkey_of(arg_matches, "holder").unwrap();
+                let trx_hash = h256_of(arg_matches, "trx_hash").unwrap();
+                let chunk_size = value_of(arg_matches, "chunk_size").unwrap_or(evm_loader::config::HOLDER_MSG_SIZE);
+                verify_holder::execute(&config, &holder, &trx_hash, chunk_size)
+            }
             _ => unreachable!(),
         };
     
+    if app_matches.is_present("metrics") {
+        eprintln!("{}", metrics::to_json(&config.rpc_client.get_transport_stats()));
+    }
+
     let exit_code: i32 =
         match result {
             Ok(_)  => 0,
             Err(e) => {
                 let error_code = e.error_code();
                 error!("NeonCli Error ({}): {}", error_code, e);
+                eprintln!("{}", serde_json::json!({ "error": e.to_json() }));
                 error_code as i32
             }
         };
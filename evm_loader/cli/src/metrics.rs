@@ -0,0 +1,30 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use solana_client::rpc_sender::RpcTransportStats;
+
+pub static EMULATIONS: AtomicU64 = AtomicU64::new(0);
+pub static STEPS_EXECUTED: AtomicU64 = AtomicU64::new(0);
+pub static ACCOUNTS_DOWNLOADED: AtomicU64 = AtomicU64::new(0);
+
+pub fn inc(counter: &AtomicU64) {
+    add(counter, 1);
+}
+
+pub fn add(counter: &AtomicU64, value: u64) {
+    counter.fetch_add(value, Ordering::Relaxed);
+}
+
+/// Account received from Solana RPC
+pub fn account_downloaded() {
+    inc(&ACCOUNTS_DOWNLOADED);
+}
+
+/// RPC requests are counted by the transport of the shared client, including retried attempts
+pub fn to_json(rpc_stats: &RpcTransportStats) -> serde_json::Value {
+    serde_json::json!({
+        "emulations": EMULATIONS.load(Ordering::Relaxed),
+        "steps_executed": STEPS_EXECUTED.load(Ordering::Relaxed),
+        "rpc_calls": rpc_stats.request_count,
+        "accounts_downloaded": ACCOUNTS_DOWNLOADED.load(Ordering::Relaxed),
+    })
+}
@@ -0,0 +1,39 @@
+use solana_client::client_error::ClientError;
+use solana_sdk::{
+    signature::{Keypair, Signer, read_keypair_file},
+    transaction::TransactionError,
+};
+
+use crate::{errors::NeonCliError, Config};
+
+
+pub fn read_keypairs<'a, I>(paths: I) -> Result<Vec<Keypair>, NeonCliError>
+    where I: Iterator<Item = &'a str>
+{
+    paths.map(|path| {
+        read_keypair_file(path).map_err(|e| {
+            let e = std::io::Error::new(std::io::ErrorKind::Other, format!("{}: {}", path, e));
+            NeonCliError::StdIoError(e)
+        })
+    })
+    .collect()
+}
+
+/// Fee payers in order of preference: configured operator keypairs, then the default signer
+pub fn fee_payers(config: &Config) -> Vec<&dyn Signer> {
+    let mut fee_payers: Vec<&dyn Signer> = config.operators.iter()
+        .map(|keypair| keypair as &dyn Signer)
+        .collect();
+    fee_payers.push(&*config.signer);
+
+    fee_payers
+}
+
+/// Transaction failed because of the fee payer state and may succeed with another fee payer
+pub fn is_contention(error: &ClientError) -> bool {
+    matches!(error.get_transaction_error(),
+        Some(TransactionError::AccountInUse) |
+        Some(TransactionError::BlockhashNotFound) |
+        Some(TransactionError::InsufficientFundsForFee)
+    )
+}
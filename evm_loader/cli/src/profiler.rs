@@ -0,0 +1,80 @@
+use std::collections::BTreeMap;
+
+use evm::{ExitReason, H160, U256};
+use evm_loader::{account_storage::AccountStorage, executor::Machine};
+
+
+/// Executed opcodes and gas charged while they were executed
+#[derive(Default, Clone, Copy)]
+struct Counter {
+    count: u64,
+    gas: U256,
+}
+
+impl Counter {
+    fn record(&mut self, gas: U256) {
+        self.count += 1;
+        self.gas = self.gas.saturating_add(gas);
+    }
+}
+
+/// Per opcode and per contract execution statistics of the emulation.
+/// Solana compute units are not available off-chain, iterative execution cost is estimated by used gas.
+#[derive(Default)]
+pub struct Profiler {
+    opcodes: BTreeMap<u8, Counter>,
+    contracts: BTreeMap<H160, Counter>,
+}
+
+impl Profiler {
+    /// Executes up to `max_steps` opcodes one by one, recording each of them.
+    /// Returns the same result as [`Machine::execute_n_steps`].
+    pub fn execute_n_steps<B: AccountStorage>(&mut self, executor: &mut Machine<B>, max_steps: u64) -> Result<(), (Vec<u8>, ExitReason)> {
+        for _ in 0..max_steps {
+            let opcode = executor.opcode();
+            let contract = executor.contract();
+            let gas_before = executor.used_gas();
+
+            let result = executor.execute_step();
+
+            let gas = executor.used_gas().saturating_sub(gas_before);
+            if let Some(opcode) = opcode {
+                self.opcodes.entry(opcode.0).or_default().record(gas);
+            }
+            if let Some(contract) = contract {
+                self.contracts.entry(contract).or_default().record(gas);
+            }
+
+            result?;
+        }
+
+        Ok(())
+    }
+
+    /// Report sorted by used gas, then by executed count
+    #[must_use]
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut opcodes: Vec<(&u8, &Counter)> = self.opcodes.iter().collect();
+        opcodes.sort_by(|(_, a), (_, b)| b.gas.cmp(&a.gas).then(b.count.cmp(&a.count)));
+
+        let mut contracts: Vec<(&H160, &Counter)> = self.contracts.iter().collect();
+        contracts.sort_by(|(_, a), (_, b)| b.gas.cmp(&a.gas).then(b.count.cmp(&a.count)));
+
+        serde_json::json!({
+            "opcodes": opcodes.into_iter()
+                .map(|(opcode, counter)| serde_json::json!({
+                    "opcode": format!("{:#04x}", opcode),
+                    "count": counter.count,
+                    "gas": counter.gas.as_u64(),
+                }))
+                .collect::<Vec<_>>(),
+            "contracts": contracts.into_iter()
+                .map(|(address, counter)| serde_json::json!({
+                    "address": format!("0x{}", hex::encode(address.as_bytes())),
+                    "steps": counter.count,
+                    "gas": counter.gas.as_u64(),
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+}
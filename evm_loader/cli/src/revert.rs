@@ -0,0 +1,165 @@
+use std::convert::TryFrom;
+
+use evm::U256;
+
+// keccak256("Error(string)")[..4]
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+// keccak256("Panic(uint256)")[..4]
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+
+fn abi_usize(data: &[u8], offset: usize) -> Option<usize> {
+    let word = data.get(offset..offset.checked_add(32)?)?;
+    let value = U256::from_big_endian(word);
+    if value > U256::from(usize::MAX) {
+        return None;
+    }
+
+    usize::try_from(value.as_u64()).ok()
+}
+
+/// Message of the `Error(string)` revert data
+pub fn decode_revert_reason(data: &[u8]) -> Option<String> {
+    if data.get(0..4)? != ERROR_SELECTOR {
+        return None;
+    }
+
+    let args = &data[4..];
+    let offset = abi_usize(args, 0)?;
+    let len = abi_usize(args, offset)?;
+
+    let begin = offset.checked_add(32)?;
+    let message = args.get(begin..begin.checked_add(len)?)?;
+
+    Some(String::from_utf8_lossy(message).into_owned())
+}
+
+/// Code of the `Panic(uint256)` revert data
+pub fn decode_panic_code(data: &[u8]) -> Option<U256> {
+    if data.get(0..4)? != PANIC_SELECTOR {
+        return None;
+    }
+
+    let code = data.get(4..36)?;
+    Some(U256::from_big_endian(code))
+}
+
+
+/// Solidity custom error definition, e.g. `InsufficientBalance(uint256 available, uint256 required)`
+pub struct ErrorAbi {
+    pub name: String,
+    pub params: Vec<String>,
+    pub selector: [u8; 4],
+}
+
+impl ErrorAbi {
+    pub fn signature(&self) -> String {
+        format!("{}({})", self.name, self.params.join(","))
+    }
+}
+
+/// `uint` and `int` are aliases for `uint256` and `int256` in signatures
+fn canonical_type(kind: &str) -> String {
+    for (alias, canonical) in [("uint", "uint256"), ("int", "int256")] {
+        if let Some(rest) = kind.strip_prefix(alias) {
+            if !rest.starts_with(|c: char| c.is_ascii_digit()) {
+                return format!("{}{}", canonical, rest);
+            }
+        }
+    }
+
+    kind.to_string()
+}
+
+impl std::str::FromStr for ErrorAbi {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        let value = value.strip_prefix("error ").unwrap_or(value).trim();
+
+        let (name, params) = value.strip_suffix(')')
+            .and_then(|v| v.split_once('('))
+            .ok_or_else(|| format!("invalid error signature '{}'", value))?;
+
+        let name = name.trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$') {
+            return Err(format!("invalid error name '{}'", name));
+        }
+        if params.contains('(') {
+            return Err(format!("tuple parameters are not supported '{}'", value));
+        }
+
+        let params: Vec<String> = params.split(',')
+            .map(str::trim)
+            .filter(|param| !param.is_empty())
+            .map(|param| canonical_type(param.split_whitespace().next().unwrap_or_default()))
+            .collect();
+
+        let mut error = Self { name: name.to_string(), params, selector: [0; 4] };
+        let hash = crate::keccak256_h256(error.signature().as_bytes());
+        error.selector.copy_from_slice(&hash[0..4]);
+
+        Ok(error)
+    }
+}
+
+fn decode_int(word: &[u8]) -> String {
+    let value = U256::from_big_endian(word);
+    if word[0] & 0x80 == 0 {
+        return value.to_string();
+    }
+
+    let magnitude = (!value).overflowing_add(U256::one()).0;
+    format!("-{}", magnitude)
+}
+
+fn decode_param(kind: &str, args: &[u8], index: usize) -> Option<serde_json::Value> {
+    let head = index.checked_mul(32)?;
+    let word = args.get(head..head.checked_add(32)?)?;
+
+    let value = match kind {
+        "string" | "bytes" => {
+            let offset = abi_usize(args, head)?;
+            let len = abi_usize(args, offset)?;
+            let begin = offset.checked_add(32)?;
+            let bytes = args.get(begin..begin.checked_add(len)?)?;
+
+            if kind == "string" {
+                String::from_utf8_lossy(bytes).into_owned().into()
+            } else {
+                format!("0x{}", hex::encode(bytes)).into()
+            }
+        },
+        "address" => format!("0x{}", hex::encode(&word[12..32])).into(),
+        "bool" => word.iter().any(|b| *b != 0).into(),
+        _ if kind.starts_with("uint") && !kind.ends_with(']') => U256::from_big_endian(word).to_string().into(),
+        _ if kind.starts_with("int") && !kind.ends_with(']') => decode_int(word).into(),
+        _ if kind.starts_with("bytes") && !kind.ends_with(']') => {
+            let len: usize = kind["bytes".len()..].parse().ok().filter(|len| *len <= 32)?;
+            format!("0x{}", hex::encode(&word[0..len])).into()
+        },
+        // Arrays are returned as raw head word
+        _ => format!("0x{}", hex::encode(word)).into(),
+    };
+
+    Some(value)
+}
+
+/// Custom error matching revert data selector, with decoded arguments
+pub fn decode_custom_error(data: &[u8], errors: &[ErrorAbi]) -> Option<serde_json::Value> {
+    let selector = data.get(0..4)?;
+    let error = errors.iter().find(|error| error.selector == selector)?;
+
+    let args = &data[4..];
+    let values: Option<Vec<serde_json::Value>> = error.params.iter()
+        .enumerate()
+        .map(|(index, kind)| decode_param(kind, args, index))
+        .collect();
+
+    Some(serde_json::json!({
+        "name": error.name,
+        "signature": error.signature(),
+        "args": values,
+    }))
+}
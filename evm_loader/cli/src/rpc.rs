@@ -0,0 +1,57 @@
+use std::{thread, time::Duration};
+
+use log::warn;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind, Result as ClientResult},
+    rpc_request::RpcError,
+};
+use solana_sdk::transaction::TransactionError;
+
+const RETRY_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY_MS: u64 = 200;
+
+// JSON RPC server error codes, see solana_client::rpc_custom_error
+const JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY: i64 = -32005;
+const JSON_RPC_SERVER_ERROR_SLOT_SKIPPED: i64 = -32007;
+
+const HTTP_TOO_MANY_REQUESTS: u16 = 429;
+
+
+/// Error is caused by the node or network state (node behind, rate limited,
+/// expired blockhash, connection failure) and the same request may succeed later
+pub fn is_transient(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(e) => {
+            e.is_timeout()
+                || e.is_connect()
+                || e.status().map_or(false, |status| status.as_u16() == HTTP_TOO_MANY_REQUESTS)
+        },
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            matches!(*code,
+                JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY |
+                JSON_RPC_SERVER_ERROR_SLOT_SKIPPED
+            )
+        },
+        ClientErrorKind::TransactionError(TransactionError::BlockhashNotFound) => true,
+        _ => false,
+    }
+}
+
+/// Repeats an idempotent RPC request while it fails with a transient error
+pub fn retry<T, F>(request: F) -> ClientResult<T>
+    where F: Fn() -> ClientResult<T>
+{
+    let mut attempt = 1;
+    loop {
+        match request() {
+            Err(e) if attempt < RETRY_ATTEMPTS && is_transient(&e) => {
+                warn!("Transient RPC error, attempt {}/{}: {}", attempt, RETRY_ATTEMPTS, e);
+
+                thread::sleep(Duration::from_millis(RETRY_BASE_DELAY_MS << (attempt - 1)));
+                attempt += 1;
+            },
+            result => return result,
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::ErrorKind,
+    path::Path,
+};
+
+use evm::{H160, U256};
+use evm_loader::{account_storage::AccountStorage, executor::Action};
+
+use crate::errors::NeonCliError;
+
+
+/// State of an Ethereum account changed by the emulations of the session
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct SessionAccount {
+    pub nonce: U256,
+    pub balance: U256,
+    #[serde(with = "serde_hex")]
+    pub code: Vec<u8>,
+    #[serde(with = "serde_hex")]
+    pub valids: Vec<u8>,
+    /// Storage is cleared, unchanged slots are zero instead of stored in Solana
+    pub destroyed: bool,
+    pub storage: BTreeMap<U256, U256>,
+}
+
+impl SessionAccount {
+    fn load<B: AccountStorage>(storage: &B, address: &H160) -> Self {
+        Self {
+            nonce: storage.nonce(address),
+            balance: storage.balance(address),
+            code: storage.code(address),
+            valids: storage.valids(address),
+            destroyed: false,
+            storage: BTreeMap::new(),
+        }
+    }
+}
+
+mod serde_hex {
+    pub fn serialize<S>(value: &[u8], s: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+        s.serialize_str(&hex::encode(value))
+    }
+
+    pub fn deserialize<'de, D>(d: D) -> Result<Vec<u8>, D::Error> where D: serde::Deserializer<'de> {
+        let value: String = serde::Deserialize::deserialize(d)?;
+        hex::decode(value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Emulation session persisted between CLI invocations.
+/// Results of successful emulations are applied on top of the Solana state,
+/// so dependent calls can be emulated one after another as against a private fork.
+#[derive(serde::Serialize, serde::Deserialize, Default, Debug)]
+pub struct Session {
+    pub accounts: BTreeMap<H160, SessionAccount>,
+}
+
+impl Session {
+    /// Reads the session file, a missing file starts a new session
+    pub fn load(path: &Path) -> Result<Self, NeonCliError> {
+        match fs::read(path) {
+            Ok(data) => serde_json::from_slice(&data)
+                .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e).into()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), NeonCliError> {
+        let data = serde_json::to_vec_pretty(self)
+            .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+        fs::write(path, data)?;
+
+        Ok(())
+    }
+
+    fn account<B: AccountStorage>(&mut self, storage: &B, address: &H160) -> &mut SessionAccount {
+        self.accounts.entry(*address)
+            .or_insert_with(|| SessionAccount::load(storage, address))
+    }
+
+    /// Applies actions of a successful emulation.
+    /// `storage` provides the state the emulation has started from.
+    pub fn apply_actions<B: AccountStorage>(&mut self, storage: &B, actions: &[Action]) {
+        for action in actions {
+            match action {
+                Action::NeonTransfer { source, target, value } => {
+                    let source = self.account(storage, source);
+                    source.balance = source.balance.saturating_sub(*value);
+
+                    let target = self.account(storage, target);
+                    target.balance = target.balance.saturating_add(*value);
+                },
+                Action::NeonWithdraw { source, value } => {
+                    let source = self.account(storage, source);
+                    source.balance = source.balance.saturating_sub(*value);
+                },
+                Action::EvmSetStorage { address, key, value } => {
+                    self.account(storage, address).storage.insert(*key, *value);
+                },
+                Action::EvmIncrementNonce { address } => {
+                    let account = self.account(storage, address);
+                    account.nonce = account.nonce.saturating_add(U256::one());
+                },
+                Action::EvmSetCode { address, code, valids } => {
+                    let account = self.account(storage, address);
+                    account.code = code.clone();
+                    account.valids = valids.clone();
+                },
+                Action::EvmSelfDestruct { address } => {
+                    let account = self.account(storage, address);
+                    account.nonce = U256::zero();
+                    account.code.clear();
+                    account.valids.clear();
+                    account.storage.clear();
+                    account.destroyed = true;
+                },
+                Action::EvmLog { .. } | Action::ExternalInstruction { .. } => {},
+            }
+        }
+    }
+}
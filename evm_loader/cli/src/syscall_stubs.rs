@@ -19,6 +19,11 @@ impl Stubs {
 
         Ok(Box::new(Self { rent }))
     }
+
+    /// Stubs with default rent, avoids RPC request when gas is not estimated
+    pub fn with_default_rent() -> Box<Stubs> {
+        Box::new(Self { rent: Rent::default() })
+    }
 }
 
 impl SyscallStubs for Stubs {
@@ -0,0 +1,168 @@
+use log::{debug, info, warn};
+
+use solana_client::{
+    client_error::ClientErrorKind,
+    rpc_config::RpcSendTransactionConfig,
+};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    hash::Hash,
+    instruction::Instruction,
+    message::Message,
+    signature::{Signature, Signer},
+    signer::SignerError,
+    transaction::{Transaction, TransactionError},
+};
+
+use crate::{errors::NeonCliError, operators, Config};
+
+
+/// Signs transactions with locally available keys
+pub trait TransactionSigner {
+    /// Adds signatures of the held keys.
+    /// Returns `false` if the transaction still lacks signatures of external signers.
+    fn sign_transaction(&self, transaction: &mut Transaction, blockhash: Hash) -> Result<bool, SignerError>;
+}
+
+/// Signer holding all required keys
+pub struct LocalSigner<'a> {
+    pub signers: Vec<&'a dyn Signer>,
+}
+
+impl TransactionSigner for LocalSigner<'_> {
+    fn sign_transaction(&self, transaction: &mut Transaction, blockhash: Hash) -> Result<bool, SignerError> {
+        transaction.try_sign(&self.signers, blockhash)?;
+        Ok(true)
+    }
+}
+
+/// Signer leaving the transaction for external co-signing (e.g. multisig)
+pub struct PartialSigner<'a> {
+    pub signers: Vec<&'a dyn Signer>,
+}
+
+impl TransactionSigner for PartialSigner<'_> {
+    fn sign_transaction(&self, transaction: &mut Transaction, blockhash: Hash) -> Result<bool, SignerError> {
+        transaction.try_partial_sign(&self.signers, blockhash)?;
+        Ok(transaction.is_signed())
+    }
+}
+
+
+fn missing_signers(transaction: &Transaction) -> Vec<String> {
+    let message = &transaction.message;
+    let required = usize::from(message.header.num_required_signatures);
+
+    message.account_keys[..required].iter()
+        .zip(transaction.signatures.iter())
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(key, _)| key.to_string())
+        .collect()
+}
+
+/// Sends a transaction of `instructions` paid by the first suitable fee payer, see `operators::fee_payers`.
+/// A fee payer with insufficient balance is skipped, and a contention error passes the transaction to the next one.
+/// Balances are not probed when the default signer is the only fee payer.
+/// In sign-only mode the default signer pays, and the partially signed transaction is printed instead; returns `None`.
+pub fn send_instructions(
+    config: &Config,
+    instructions: &[Instruction],
+    commitment: CommitmentConfig,
+) -> Result<Option<Signature>, NeonCliError> {
+    let fee_payers = operators::fee_payers(config);
+    if config.sign_only || fee_payers.len() == 1 {
+        return send_with_fee_payer(config, instructions, &*config.signer, commitment);
+    }
+
+    let mut last_error = None;
+    for fee_payer in fee_payers {
+        let message = Message::new(instructions, Some(&fee_payer.pubkey()));
+
+        let fee = config.rpc_client.get_fee_for_message(&message)?;
+        let balance = config.rpc_client.get_balance(&fee_payer.pubkey())?;
+        if balance < fee {
+            warn!("Fee payer {} has insufficient balance {} < {}", fee_payer.pubkey(), balance, fee);
+            continue;
+        }
+
+        match send_with_fee_payer(config, instructions, fee_payer, commitment) {
+            Err(NeonCliError::ClientError(e)) if operators::is_contention(&e) => {
+                warn!("Fee payer {} failed to send transaction: {}", fee_payer.pubkey(), e);
+                last_error = Some(e);
+            },
+            result => return result,
+        }
+    }
+
+    let error = last_error.unwrap_or_else(|| {
+        ClientErrorKind::TransactionError(TransactionError::InsufficientFundsForFee).into()
+    });
+    Err(NeonCliError::ClientError(error))
+}
+
+fn send_with_fee_payer(
+    config: &Config,
+    instructions: &[Instruction],
+    fee_payer: &dyn Signer,
+    commitment: CommitmentConfig,
+) -> Result<Option<Signature>, NeonCliError> {
+    let mut message = Message::new(instructions, Some(&fee_payer.pubkey()));
+    let (blockhash, _last_valid_slot) = config.rpc_client.get_latest_blockhash_with_commitment(commitment)?;
+    message.recent_blockhash = blockhash;
+
+    let mut signers = vec![fee_payer];
+    let signer_pubkey = config.signer.pubkey();
+    let signer_required = message.account_keys[..usize::from(message.header.num_required_signatures)]
+        .contains(&signer_pubkey);
+    if signer_required && signer_pubkey != fee_payer.pubkey() {
+        signers.push(&*config.signer);
+    }
+
+    sign_and_send(config, signers, Transaction::new_unsigned(message), blockhash, commitment)
+}
+
+/// Signs the transaction with `signers` and sends it.
+/// In sign-only mode prints the partially signed transaction instead and returns `None`.
+fn sign_and_send(
+    config: &Config,
+    signers: Vec<&dyn Signer>,
+    mut transaction: Transaction,
+    blockhash: Hash,
+    commitment: CommitmentConfig,
+) -> Result<Option<Signature>, NeonCliError> {
+    let signer: Box<dyn TransactionSigner> = if config.sign_only {
+        Box::new(PartialSigner { signers })
+    } else {
+        Box::new(LocalSigner { signers })
+    };
+
+    let is_signed = signer.sign_transaction(&mut transaction, blockhash)?;
+    debug!("signed: {:x?}", transaction);
+
+    if config.sign_only {
+        let serialized = bincode::serialize(&transaction)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
+
+        let js = serde_json::json!({
+            "blockhash": blockhash.to_string(),
+            "is_signed": is_signed,
+            "missing_signers": missing_signers(&transaction),
+            "transaction": base64::encode(serialized),
+        });
+        println!("{}", js);
+
+        return Ok(None);
+    }
+
+    let signature = config.rpc_client.send_and_confirm_transaction_with_spinner_and_config(
+        &transaction,
+        commitment,
+        RpcSendTransactionConfig {
+            preflight_commitment: Some(commitment.commitment),
+            ..RpcSendTransactionConfig::default()
+        },
+    )?;
+    info!("transaction {} confirmed", signature);
+
+    Ok(Some(signature))
+}
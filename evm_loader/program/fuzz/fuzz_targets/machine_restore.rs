@@ -0,0 +1,89 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use evm::{H160, H256, U256};
+use solana_program::{
+    account_info::AccountInfo,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+};
+
+use evm_loader::{
+    account::State,
+    account_storage::AccountStorage,
+    executor::{Machine, OwnedAccountInfo, OwnedAccountInfoPartial},
+};
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_get_rent_sysvar(&self, pointer: *mut u8) -> u64 {
+        unsafe {
+            #[allow(clippy::cast_ptr_alignment)]
+            let rent = pointer.cast::<Rent>();
+            *rent = Rent::default();
+        }
+
+        0
+    }
+}
+
+/// Backend without accounts, restoring the state must not depend on it
+struct EmptyAccountStorage {
+    program_id: Pubkey,
+}
+
+impl AccountStorage for EmptyAccountStorage {
+    fn neon_token_mint(&self) -> &Pubkey { &self.program_id }
+    fn program_id(&self) -> &Pubkey { &self.program_id }
+    fn operator(&self) -> &Pubkey { &self.program_id }
+    fn block_number(&self) -> U256 { U256::zero() }
+    fn block_timestamp(&self) -> U256 { U256::zero() }
+    fn block_hash(&self, _number: U256) -> H256 { H256::default() }
+    fn chain_id(&self) -> u64 { 0 }
+    fn exists(&self, _address: &H160) -> bool { false }
+    fn nonce(&self, _address: &H160) -> U256 { U256::zero() }
+    fn balance(&self, _address: &H160) -> U256 { U256::zero() }
+    fn code_size(&self, _address: &H160) -> usize { 0 }
+    fn code_hash(&self, _address: &H160) -> H256 { H256::default() }
+    fn code(&self, _address: &H160) -> Vec<u8> { Vec::new() }
+    fn valids(&self, _address: &H160) -> Vec<u8> { Vec::new() }
+    fn generation(&self, _address: &H160) -> u32 { 0 }
+    fn storage(&self, _address: &H160, _index: &U256) -> U256 { U256::zero() }
+    fn clone_solana_account(&self, address: &Pubkey) -> OwnedAccountInfo {
+        OwnedAccountInfo {
+            key: *address,
+            is_signer: false,
+            is_writable: false,
+            lamports: 0,
+            data: vec![],
+            owner: solana_program::system_program::ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+    fn clone_solana_account_partial(&self, _address: &Pubkey, _offset: usize, _len: usize) -> Option<OwnedAccountInfoPartial> { None }
+    fn solana_address(&self, _address: &H160) -> (Pubkey, u8) { (self.program_id, 0) }
+    fn solana_accounts_space(&self, _address: &H160) -> (usize, usize) { (0, 0) }
+}
+
+// Fuzz input is the serialized EVM state following the state account header without stored accounts
+fuzz_target!(|data: &[u8]| {
+    set_syscall_stubs(Box::new(Stubs));
+
+    let backend = EmptyAccountStorage { program_id: Pubkey::new_from_array([1; 32]) };
+    let key = Pubkey::new_from_array([2; 32]);
+
+    let mut account_data = vec![0_u8; State::SIZE];
+    account_data[0] = State::TAG;
+    account_data.extend_from_slice(data);
+
+    let mut lamports = 0_u64;
+    let info = AccountInfo::new(&key, false, true, &mut lamports, &mut account_data, &backend.program_id, false, 0);
+    let storage = State::from_account(&backend.program_id, &info).unwrap();
+
+    if let Ok(machine) = Machine::restore(&storage, &backend) {
+        let _ = machine.into_state_actions();
+    }
+});
@@ -0,0 +1,21 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+
+use std::convert::TryInto;
+
+use evm_loader::transaction::{UnsignedTransaction, verify_tx_signature};
+
+// Instruction data layout: signature (65 bytes) | unsigned transaction RLP
+fuzz_target!(|data: &[u8]| {
+    if data.len() < 65 {
+        let _ = UnsignedTransaction::from_rlp(data);
+        return;
+    }
+
+    let (signature, unsigned_msg) = data.split_at(65);
+    let signature: &[u8; 65] = signature.try_into().unwrap();
+
+    if UnsignedTransaction::from_rlp(unsigned_msg).is_ok() {
+        let _ = verify_tx_signature(signature, unsigned_msg);
+    }
+});
@@ -220,6 +220,10 @@ pub struct Token<'a>(&'a AccountInfo<'a>);
 
 impl<'a> Token<'a> {
     pub fn from_account(info: &'a AccountInfo<'a>) -> Result<Self, ProgramError> {
+        if token::token_2022::check_id(info.key) {
+            return Err!(ProgramError::IncorrectProgramId; "Account {} - Token-2022 program is not supported", info.key);
+        }
+
         if !spl_token::check_id(info.key) {
             return Err!(ProgramError::InvalidArgument; "Account {} - is not token program", info.key);
         }
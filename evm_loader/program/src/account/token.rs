@@ -3,6 +3,12 @@ use solana_program::program_error::ProgramError;
 use solana_program::program_pack::{IsInitialized, Pack};
 use std::ops::Deref;
 
+/// SPL Token-2022 program. Its accounts are not supported yet
+/// and are rejected explicitly instead of failing on the owner check.
+pub mod token_2022 {
+    solana_program::declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+}
+
 pub struct Account<'a, T: Pack + IsInitialized> {
     pub info: &'a AccountInfo<'a>,
     data: T,
@@ -10,6 +16,10 @@ pub struct Account<'a, T: Pack + IsInitialized> {
 
 impl<'a, T: Pack + IsInitialized> Account<'a, T> {
     pub fn from_account(info: &'a AccountInfo<'a>) -> Result<Self, ProgramError> {
+        if token_2022::check_id(info.owner) {
+            return Err!(ProgramError::IncorrectProgramId; "Account {} - Token-2022 accounts are not supported", info.key);
+        }
+
         if !spl_token::check_id(info.owner) {
             return Err!(ProgramError::InvalidArgument; "Account {} - is not spl token owned", info.key);
         }
@@ -9,6 +9,7 @@ use crate::account::{ACCOUNT_SEED_VERSION, EthereumAccount, EthereumStorage, Ope
 use crate::account_storage::{Account, AccountStorage, ProgramAccountStorage};
 use crate::executor::{Action, AccountMeta};
 use crate::config::STORAGE_ENTIRIES_IN_CONTRACT_ACCOUNT;
+use crate::event;
 use solana_program::program::{invoke_signed_unchecked};
 
 
@@ -102,7 +103,14 @@ impl<'a> ProgramAccountStorage<'a> {
                     }
 
                     let instruction = Instruction { program_id, accounts, data: instruction };
-                    invoke_signed_unchecked(&instruction, &accounts_info, &[&seeds[..]])?;
+                    let result = invoke_signed_unchecked(&instruction, &accounts_info, &[&seeds[..]]);
+                    event!(ExternalCall {
+                        program_id,
+                        accounts: &instruction.accounts,
+                        data: &instruction.data,
+                        result: &result,
+                    });
+                    result?;
                 }
             }
         }
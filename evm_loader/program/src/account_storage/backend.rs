@@ -4,7 +4,7 @@ use solana_program::{
     pubkey::Pubkey,
     sysvar::recent_blockhashes
 };
-use crate::account::{EthereumContract, EthereumStorage, ACCOUNT_SEED_VERSION};
+use crate::account::{EthereumContract, EthereumStorage};
 use crate::account_storage::{AccountStorage, ProgramAccountStorage};
 use crate::config::STORAGE_ENTIRIES_IN_CONTRACT_ACCOUNT;
 use crate::executor::{OwnedAccountInfo, OwnedAccountInfoPartial};
@@ -151,10 +151,14 @@ impl<'a> AccountStorage for ProgramAccountStorage<'a> {
         match self.ethereum_accounts.get(address) {
             Some(Account::User(a)) => (*a.info.key, a.bump_seed),
             Some(Account::Contract(a, _)) => (*a.info.key, a.bump_seed),
-            None => Pubkey::find_program_address(&[&[ACCOUNT_SEED_VERSION], address.as_bytes()], self.program_id)
+            None => self.keys.ethereum_account(self.program_id, address)
         }
     }
 
+    fn get_storage_address(&self, address: &H160, index: &U256) -> (Pubkey, u8) {
+        self.keys.storage_cell(self.program_id, address, self.generation(address), index)
+    }
+
     fn chain_id(&self) -> u64 {
         crate::config::CHAIN_ID
     }
@@ -7,8 +7,8 @@ use solana_program::program_error::ProgramError;
 use solana_program::pubkey::Pubkey;
 use solana_program::system_program;
 use solana_program::sysvar::Sysvar;
-use crate::account::{ACCOUNT_SEED_VERSION, EthereumAccount, EthereumContract, Operator, program};
-use crate::account_storage::{Account, ProgramAccountStorage};
+use crate::account::{EthereumAccount, EthereumContract, Operator, program};
+use crate::account_storage::{Account, KeysCache, ProgramAccountStorage};
 
 
 
@@ -70,6 +70,7 @@ impl<'a> ProgramAccountStorage<'a> {
             solana_accounts,
             ethereum_accounts,
             empty_ethereum_accounts: RefCell::new(BTreeSet::new()),
+            keys: KeysCache::new(),
         })
     }
 
@@ -83,7 +84,7 @@ impl<'a> ProgramAccountStorage<'a> {
             return;
         }
 
-        let (solana_address, _) = Pubkey::find_program_address(&[&[ACCOUNT_SEED_VERSION], address.as_bytes()], self.program_id);
+        let (solana_address, _) = self.keys.ethereum_account(self.program_id, address);
         if let Some(account) = self.solana_accounts.get(&solana_address) {
             assert!(system_program::check_id(account.owner), "Empty ethereum account {} must belong to the system program", address);
 
@@ -0,0 +1,123 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use evm::{H160, U256};
+use solana_program::pubkey::Pubkey;
+use crate::account::ACCOUNT_SEED_VERSION;
+
+
+/// Program derived addresses computed during the transaction.
+/// `find_program_address` costs up to several thousands compute units, each key is derived once.
+#[derive(Default)]
+pub struct KeysCache {
+    accounts: RefCell<BTreeMap<H160, (Pubkey, u8)>>,
+    storage_cells: RefCell<BTreeMap<(H160, u32, U256), (Pubkey, u8)>>,
+}
+
+impl KeysCache {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ethereum account address and bump seed
+    pub fn ethereum_account(&self, program_id: &Pubkey, address: &H160) -> (Pubkey, u8) {
+        *self.accounts.borrow_mut()
+            .entry(*address)
+            .or_insert_with(|| Self::find_ethereum_account(program_id, address))
+    }
+
+    /// Storage cell address and bump seed
+    pub fn storage_cell(&self, program_id: &Pubkey, address: &H160, generation: u32, index: &U256) -> (Pubkey, u8) {
+        let mut index_bytes = [0_u8; 32];
+
+        *self.storage_cells.borrow_mut()
+            .entry((*address, generation, *index))
+            .or_insert_with(|| Self::find_storage_cell(program_id, address, generation, index, &mut index_bytes))
+    }
+
+    fn find_ethereum_account(program_id: &Pubkey, address: &H160) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[&[ACCOUNT_SEED_VERSION], address.as_bytes()], program_id)
+    }
+
+    fn find_storage_cell(program_id: &Pubkey, address: &H160, generation: u32, index: &U256, index_bytes: &mut [u8; 32]) -> (Pubkey, u8) {
+        let generation_bytes = generation.to_le_bytes();
+        index.to_little_endian(index_bytes);
+
+        let seeds: &[&[u8]] = &[&[ACCOUNT_SEED_VERSION], b"ContractStorage", address.as_bytes(), &generation_bytes, index_bytes];
+        Pubkey::find_program_address(seeds, program_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use evm::{H160, H256, U256};
+    use solana_program::pubkey::Pubkey;
+
+    use crate::account_storage::AccountStorage;
+    use crate::executor::{OwnedAccountInfo, OwnedAccountInfoPartial};
+
+    use super::KeysCache;
+
+    /// Storage with the default `get_storage_address`, only the program id and generation are used
+    struct Storage {
+        program_id: Pubkey,
+        generation: u32,
+    }
+
+    impl AccountStorage for Storage {
+        fn neon_token_mint(&self) -> &Pubkey { unimplemented!() }
+        fn program_id(&self) -> &Pubkey { &self.program_id }
+        fn operator(&self) -> &Pubkey { unimplemented!() }
+
+        fn block_number(&self) -> U256 { unimplemented!() }
+        fn block_timestamp(&self) -> U256 { unimplemented!() }
+        fn block_hash(&self, _number: U256) -> H256 { unimplemented!() }
+        fn chain_id(&self) -> u64 { unimplemented!() }
+
+        fn exists(&self, _address: &H160) -> bool { unimplemented!() }
+        fn nonce(&self, _address: &H160) -> U256 { unimplemented!() }
+        fn balance(&self, _address: &H160) -> U256 { unimplemented!() }
+
+        fn code_size(&self, _address: &H160) -> usize { unimplemented!() }
+        fn code_hash(&self, _address: &H160) -> H256 { unimplemented!() }
+        fn code(&self, _address: &H160) -> Vec<u8> { unimplemented!() }
+        fn valids(&self, _address: &H160) -> Vec<u8> { unimplemented!() }
+        fn generation(&self, _address: &H160) -> u32 { self.generation }
+
+        fn storage(&self, _address: &H160, _index: &U256) -> U256 { unimplemented!() }
+
+        fn clone_solana_account(&self, _address: &Pubkey) -> OwnedAccountInfo { unimplemented!() }
+        fn clone_solana_account_partial(&self, _address: &Pubkey, _offset: usize, _len: usize) -> Option<OwnedAccountInfoPartial> { unimplemented!() }
+
+        fn solana_address(&self, _address: &H160) -> (Pubkey, u8) { unimplemented!() }
+        fn solana_accounts_space(&self, _address: &H160) -> (usize, usize) { unimplemented!() }
+    }
+
+    #[test]
+    fn test_storage_cell_matches_default_storage_address() {
+        let program_id = Pubkey::new_unique();
+        let address = H160::repeat_byte(0x11);
+        let cache = KeysCache::new();
+
+        for generation in [0_u32, 1, 7] {
+            let storage = Storage { program_id, generation };
+            for index in [U256::from(256_u64), U256::from(0x1234_5678_u64), U256::MAX] {
+                let expected = storage.get_storage_address(&address, &index);
+                assert_eq!(cache.storage_cell(&program_id, &address, generation, &index), expected);
+                // cached value
+                assert_eq!(cache.storage_cell(&program_id, &address, generation, &index), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ethereum_account_matches_program_address() {
+        let program_id = Pubkey::new_unique();
+        let cache = KeysCache::new();
+
+        for address in [H160::zero(), H160::repeat_byte(0x22)] {
+            let expected = Pubkey::find_program_address(&[&[crate::account::ACCOUNT_SEED_VERSION], address.as_bytes()], &program_id);
+            assert_eq!(cache.ethereum_account(&program_id, &address), expected);
+        }
+    }
+}
@@ -10,6 +10,9 @@ use solana_program::clock::Clock;
 mod base;
 mod apply;
 mod backend;
+mod keys_cache;
+
+pub use keys_cache::KeysCache;
 
 
 enum Account<'a> {
@@ -25,6 +28,7 @@ pub struct ProgramAccountStorage<'a> {
     solana_accounts: BTreeMap<Pubkey, &'a AccountInfo<'a>>,
     ethereum_accounts: BTreeMap<H160, Account<'a>>,
     empty_ethereum_accounts: RefCell<BTreeSet<H160>>,
+    keys: KeysCache,
 }
 
 /// Account storage
@@ -587,6 +587,18 @@ pub const REQUEST_UNITS_ADDITIONAL_FEE: u32 = 0;
 pub const GAS_LIMIT_MULTIPLIER_NO_CHAINID: u32 = 1000;
 /// Amount of storage enties strored in the contract account
 pub const STORAGE_ENTIRIES_IN_CONTRACT_ACCOUNT: u32 = 64;
+/// Transactions without chain id are rejected instead of being executed with a deprecation notice
+pub const NO_CHAINID_REJECTED: bool = cfg!(feature = "reject-no-chainid");
+/// Verbosity of the program logs, selected by the `no-logs` and `log-level-*` features
+pub const LOG_LEVEL: &str = if cfg!(feature = "no-logs") {
+    "off"
+} else if cfg!(feature = "log-level-error") {
+    "error"
+} else if cfg!(feature = "log-level-info") {
+    "info"
+} else {
+    "debug"
+};
 
 neon_elf_param!( NEON_PKG_VERSION           , env!("CARGO_PKG_VERSION"));
 neon_elf_param!( NEON_REVISION              , env!("NEON_REVISION"));
@@ -602,6 +614,8 @@ neon_elf_param!( NEON_HEAP_FRAME            , formatcp!("{:?}", COMPUTE_BUDGET_H
 neon_elf_param!( NEON_ADDITIONAL_FEE        , formatcp!("{:?}", REQUEST_UNITS_ADDITIONAL_FEE));
 neon_elf_param!( NEON_GAS_LIMIT_MULTIPLIER_NO_CHAINID, formatcp!("{:?}", GAS_LIMIT_MULTIPLIER_NO_CHAINID));
 neon_elf_param!( NEON_STORAGE_ENTIRIES_IN_CONTRACT_ACCOUNT, formatcp!("{:?}", STORAGE_ENTIRIES_IN_CONTRACT_ACCOUNT));
+neon_elf_param!( NEON_NO_CHAINID_REJECTED   , formatcp!("{:?}", NO_CHAINID_REJECTED));
+neon_elf_param!( NEON_LOG_LEVEL             , LOG_LEVEL);
 
 /// Chain ID
 #[must_use]
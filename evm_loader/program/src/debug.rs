@@ -1,18 +1,41 @@
+//! Logging macros. Verbosity is selected at compile time with the features:
+//! `log-level-info` keeps informational messages only, `log-level-error` and `no-logs`
+//! drop both informational and debug messages. Without these features everything is logged.
+//! Errors reported by `Err!`/`E!`, `sol_log_data` events and `msg!` output (instruction names
+//! parsed by the proxy and indexers) are not affected.
 
-#[cfg(all(target_arch = "bpf", not(feature = "no-logs")))]
+#[cfg(all(target_arch = "bpf", not(any(feature = "no-logs", feature = "log-level-error", feature = "log-level-info"))))]
 #[macro_export]
 macro_rules! debug_print {
     ($( $args:expr ),*) => { solana_program::msg!( $( $args ),* ) }
 }
 
-#[cfg(all(not(target_arch = "bpf"), not(feature = "no-logs")))]
+#[cfg(all(not(target_arch = "bpf"), not(any(feature = "no-logs", feature = "log-level-error", feature = "log-level-info"))))]
 #[macro_export]
 macro_rules! debug_print {
     ($( $args:expr ),*) => { log::debug!( $( $args ),* ) }
 }
 
-#[cfg(feature = "no-logs")]
+#[cfg(any(feature = "no-logs", feature = "log-level-error", feature = "log-level-info"))]
 #[macro_export]
 macro_rules! debug_print {
     ($( $args:expr ),*) => {}
 }
+
+#[cfg(all(target_arch = "bpf", not(any(feature = "no-logs", feature = "log-level-error"))))]
+#[macro_export]
+macro_rules! info_print {
+    ($( $args:expr ),*) => { solana_program::msg!( $( $args ),* ) }
+}
+
+#[cfg(all(not(target_arch = "bpf"), not(any(feature = "no-logs", feature = "log-level-error"))))]
+#[macro_export]
+macro_rules! info_print {
+    ($( $args:expr ),*) => { log::info!( $( $args ),* ) }
+}
+
+#[cfg(any(feature = "no-logs", feature = "log-level-error"))]
+#[macro_export]
+macro_rules! info_print {
+    ($( $args:expr ),*) => {}
+}
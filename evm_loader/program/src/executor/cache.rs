@@ -2,7 +2,7 @@ use std::{collections::BTreeMap, rc::Rc, cell::RefCell};
 
 use borsh::{BorshSerialize, BorshDeserialize};
 use solana_program::{pubkey::Pubkey, account_info::AccountInfo};
-use evm::{U256};
+use evm::{H160, H256, U256};
 
 use crate::account_storage::AccountStorage;
 
@@ -124,10 +124,15 @@ pub struct Cache {
     pub solana_accounts_partial: BTreeMap<Pubkey, OwnedAccountInfoPartial>,
     pub block_number: U256,
     pub block_timestamp: U256,
+    pub code_hashes: BTreeMap<H160, H256>,
 }
 
 impl Cache {
     pub fn get_account_or_insert<B: AccountStorage>(&mut self, key: Pubkey, backend: &B) -> &mut OwnedAccountInfo {
         self.solana_accounts.entry(key).or_insert_with(|| backend.clone_solana_account(&key))
     }
+
+    pub fn get_code_hash_or_insert<B: AccountStorage>(&mut self, address: H160, backend: &B) -> H256 {
+        *self.code_hashes.entry(address).or_insert_with(|| backend.code_hash(&address))
+    }
 }
\ No newline at end of file
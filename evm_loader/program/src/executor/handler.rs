@@ -8,12 +8,47 @@ use crate::{
 use super::{state::ExecutorState, gasometer::Gasometer};
 
 
+/// Kind of an inner call frame
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CallType {
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+    Create,
+    Create2,
+}
+
+impl CallType {
+    /// Kind of a message call made by `opcode`.
+    /// Static flag of the frame can't be used, it is inherited by all calls of a static frame.
+    #[must_use]
+    pub fn of_call(opcode: evm::Opcode) -> Self {
+        match opcode {
+            evm::Opcode::CALLCODE => Self::CallCode,
+            evm::Opcode::DELEGATECALL => Self::DelegateCall,
+            evm::Opcode::STATICCALL => Self::StaticCall,
+            _ => Self::Call,
+        }
+    }
+
+    #[must_use]
+    pub fn of_create(scheme: evm::CreateScheme) -> Self {
+        match scheme {
+            evm::CreateScheme::Create2 { .. } => Self::Create2,
+            _ => Self::Create,
+        }
+    }
+}
+
 pub struct CallInterrupt {
     pub context: evm::Context,
     pub transfer: Option<evm::Transfer>,
     pub code_address: H160,
     pub input: Vec<u8>,
     pub is_static: bool,
+    pub call_type: CallType,
+    pub target_gas: Option<u64>,
 }
 
 pub struct CreateInterrupt {
@@ -21,6 +56,8 @@ pub struct CreateInterrupt {
     pub transfer: Option<evm::Transfer>,
     pub address: H160,
     pub init_code: Vec<u8>,
+    pub call_type: CallType,
+    pub target_gas: Option<u64>,
 }
 
 
@@ -31,6 +68,8 @@ pub struct Executor<'a, B: AccountStorage> {
     pub origin: H160,
     pub gas_limit: U256,
     pub gas_price: U256,
+    /// Opcode being executed, set before every step
+    pub opcode: evm::Opcode,
 }
 
 
@@ -42,10 +81,7 @@ impl<'a, B: AccountStorage> Executor<'a, B> {
             },
             evm::CreateScheme::Legacy { caller } => {
                 let nonce = self.nonce(caller);
-                let mut stream = rlp::RlpStream::new_list(2);
-                stream.append(&caller);
-                stream.append(&nonce);
-                crate::utils::keccak256_h256(&stream.out()).into()
+                crate::utils::create_address(&caller, nonce)
             },
             evm::CreateScheme::Fixed(naddress) => {
                 naddress
@@ -83,6 +119,23 @@ impl<'a, B: AccountStorage> Executor<'a, B> {
         Ok(())
     }
 
+    /// Begins a call without sender nonce increment and value transfer, for read-only emulation
+    pub fn call_begin_read_only(
+        &mut self,
+        #[allow(unused)] caller: H160,
+        address: H160,
+        #[allow(unused)] data: &[u8],
+        #[allow(unused)] value: U256,
+        gas_limit: U256,
+    ) {
+        event!(TransactCall { caller, address, value, data, gas_limit });
+
+        self.gas_limit = gas_limit;
+        self.gas_price = U256::zero();
+
+        self.state.enter(false);
+    }
+
     pub fn create_begin(
         &mut self,
         origin: H160,
@@ -247,7 +300,7 @@ impl<'a, B: AccountStorage> Handler for Executor<'a, B> {
         scheme: evm::CreateScheme,
         value: U256,
         init_code: Vec<u8>,
-        #[allow(unused_variables)] target_gas: Option<u64>,
+        target_gas: Option<u64>,
     ) -> Capture<(ExitReason, Option<H160>, Vec<u8>), Self::CreateInterrupt> {
         debug_print!("create");
 
@@ -290,7 +343,9 @@ impl<'a, B: AccountStorage> Handler for Executor<'a, B> {
 
         let transfer = Some(evm::Transfer { source: caller, target: address, value });
 
-        Capture::Trap(CreateInterrupt{context, transfer, address, init_code})
+        let call_type = CallType::of_create(scheme);
+
+        Capture::Trap(CreateInterrupt{context, transfer, address, init_code, call_type, target_gas})
     }
 
     fn call(
@@ -298,7 +353,7 @@ impl<'a, B: AccountStorage> Handler for Executor<'a, B> {
         code_address: H160,
         transfer: Option<evm::Transfer>,
         input: Vec<u8>,
-        #[allow(unused_variables)] target_gas: Option<u64>,
+        target_gas: Option<u64>,
         is_static: bool,
         context: evm::Context,
     ) -> Capture<(ExitReason, Vec<u8>), Self::CallInterrupt> {
@@ -319,20 +374,39 @@ impl<'a, B: AccountStorage> Handler for Executor<'a, B> {
             }
         }
 
+        let call_type = CallType::of_call(self.opcode);
+
         let precompile_result = call_precompile(code_address, &input, &context, &mut self.state, &mut self.gasometer);
         if let Some(Capture::Exit(exit_value)) = precompile_result {
+            // Precompiles do not enter a new state frame, the callee is one level below the caller
+            event!(EnterCall {
+                call_type,
+                depth: self.state.call_depth(),
+                from: context.caller,
+                to: code_address,
+                value: context.apparent_value,
+                gas: target_gas,
+                input: &input,
+            });
+            event!(ExitCall {
+                depth: self.state.call_depth(),
+                reason: &exit_value.0,
+                return_value: &exit_value.1,
+            });
+
             return Capture::Exit(exit_value);
         }
 
-        Capture::Trap(CallInterrupt{context, transfer, code_address, input, is_static})
+        Capture::Trap(CallInterrupt{context, transfer, code_address, input, is_static, call_type, target_gas})
     }
 
     fn pre_validate(
         &mut self,
         _context: &evm::Context,
-        _opcode: evm::Opcode,
+        opcode: evm::Opcode,
         _stack: &evm::Stack,
     ) -> Result<(), ExitError> {
+        self.opcode = opcode;
         Ok(())
     }
 }
@@ -1,9 +1,9 @@
 use borsh::{BorshSerialize, BorshDeserialize};
-use evm::{H160, U256, ExitReason, Capture, ExitFatal, Resolve, CONFIG, Control, ExitError, Handler};
+use evm::{H160, H256, U256, ExitReason, Capture, ExitFatal, Resolve, CONFIG, Control, ExitError, Handler};
 use solana_program::{program_error::ProgramError, entrypoint::ProgramResult};
 
 use crate::{
-    emit_exit,
+    emit_exit, event,
     account_storage::AccountStorage
 };
 
@@ -32,6 +32,27 @@ enum RuntimeApply {
 
 type RuntimeInfo = (evm::Runtime, CreateReason);
 
+/// Tag preceding the layout version of the serialized runtime and executor state.
+/// Legacy state starts with the Borsh `u32` length of the runtime stack instead.
+/// Read as a length, the tag is over a billion frames, more than any storage account can hold.
+const MACHINE_STATE_TAG: [u8; 4] = *b"NEVM";
+
+/// Layout version of the serialized runtime and executor state.
+/// Increment when `RuntimeInfo` or `ExecutorState` serialization changes,
+/// including the Borsh encoding of the runtime stack and memory in the `evm` crate.
+const MACHINE_STATE_VERSION: u8 = 2;
+
+/// Reads the state tag and layout version.
+/// Returns `None` for legacy or unknown data without the tag.
+fn read_state_version(buffer: &mut &[u8]) -> Option<u8> {
+    let tag = <[u8; 4]>::deserialize(buffer).ok()?;
+    if tag != MACHINE_STATE_TAG {
+        return None;
+    }
+
+    u8::deserialize(buffer).ok()
+}
+
 /// Represents a virtual machine.
 pub struct Machine<'a, B: AccountStorage> {
     executor: Executor<'a, B>,
@@ -47,7 +68,8 @@ impl<'a, B: AccountStorage> Machine<'a, B> {
         
         let executor = Executor { 
             origin, state, gasometer, 
-            gas_limit: U256::zero(), gas_price: U256::zero() 
+            gas_limit: U256::zero(), gas_price: U256::zero(),
+            opcode: evm::Opcode::STOP,
         };
         Ok(Self { executor, runtime: Vec::new(), steps_executed: 0 })
     }
@@ -60,6 +82,8 @@ impl<'a, B: AccountStorage> Machine<'a, B> {
     pub fn save_into(&self, storage: &mut crate::account::State) {
         let mut buffer: &mut [u8] = &mut storage.evm_state_mut_data();
 
+        MACHINE_STATE_TAG.serialize(&mut &mut buffer).unwrap();
+        MACHINE_STATE_VERSION.serialize(&mut &mut buffer).unwrap();
         self.runtime.serialize(&mut &mut buffer).unwrap();
         self.executor.state.serialize(&mut &mut buffer).unwrap();
     }
@@ -68,13 +92,26 @@ impl<'a, B: AccountStorage> Machine<'a, B> {
     pub fn restore(storage: &crate::account::State, backend: &'a B) -> Result<Self, ProgramError> {
         let mut buffer: &[u8] = &storage.evm_state_data();
 
-        let runtime = BorshDeserialize::deserialize(&mut buffer).unwrap();
-        let state = ExecutorState::deserialize(&mut buffer, backend).unwrap();
+        match read_state_version(&mut buffer) {
+            Some(MACHINE_STATE_VERSION) => {},
+            Some(version) => {
+                return Err!(ProgramError::InvalidAccountData; "Account {} - unsupported EVM state version {}, expected {}", storage.info.key, version, MACHINE_STATE_VERSION);
+            },
+            None => {
+                return Err!(ProgramError::InvalidAccountData; "Account {} - EVM state has legacy or unknown layout", storage.info.key);
+            },
+        }
+
+        let runtime = BorshDeserialize::deserialize(&mut buffer)
+            .map_err(|e| E!(ProgramError::InvalidAccountData; "Account {} - failed to restore EVM runtime: {:?}", storage.info.key, e))?;
+        let state = ExecutorState::deserialize(&mut buffer, backend)
+            .map_err(|e| E!(ProgramError::InvalidAccountData; "Account {} - failed to restore EVM state: {:?}", storage.info.key, e))?;
 
         let gasometer = Gasometer::new(Some(storage.gas_used_and_paid))?;
         let executor = Executor { 
             origin: storage.caller, state, gasometer,
-            gas_limit: storage.gas_limit, gas_price: storage.gas_price
+            gas_limit: storage.gas_limit, gas_price: storage.gas_price,
+            opcode: evm::Opcode::STOP,
         };
 
         Ok(Self { executor, runtime, steps_executed: 0 })
@@ -110,6 +147,29 @@ impl<'a, B: AccountStorage> Machine<'a, B> {
         Ok(())
     }
 
+    /// Begins a read-only call of an Ethereum smart contract (`eth_call`).
+    /// Caller nonce and balance are not checked or changed, `transfer_value` is only visible to the callee.
+    pub fn call_begin_read_only(
+        &mut self,
+        caller: H160,
+        code_address: H160,
+        input: Vec<u8>,
+        transfer_value: U256,
+        gas_limit: U256,
+    ) {
+        debug_print!("call_begin_read_only");
+
+        self.executor.call_begin_read_only(caller, code_address, &input, transfer_value, gas_limit);
+
+        let code = self.executor.code(code_address);
+        let valids = self.executor.valids(code_address);
+        let context = evm::Context{ address: code_address, caller, apparent_value: transfer_value };
+
+        let runtime = evm::Runtime::new(code, valids, input, context);
+
+        self.runtime.push((runtime, CreateReason::Call));
+    }
+
     /// Begins a creation (deployment) of an Ethereum smart contract.
     ///
     /// # Errors
@@ -204,9 +264,28 @@ impl<'a, B: AccountStorage> Machine<'a, B> {
 
         self.executor.state.enter(interrupt.is_static);
 
+        event!(EnterCall {
+            call_type: interrupt.call_type,
+            depth: self.executor.state.call_depth() - 1,
+            from: interrupt.context.caller,
+            to: interrupt.code_address,
+            value: interrupt.context.apparent_value,
+            gas: interrupt.target_gas,
+            input: &interrupt.input,
+        });
+
         if let Some(transfer) = interrupt.transfer {
             self.executor.gasometer.record_transfer(&self.executor.state, interrupt.code_address, transfer.value);
-            self.executor.transfer(transfer).map_err(|e| (Vec::new(), e.into()))?;
+            if let Err(error) = self.executor.transfer(transfer) {
+                let reason: ExitReason = error.into();
+                event!(ExitCall {
+                    depth: self.executor.state.call_depth() - 1,
+                    reason: &reason,
+                    return_value: &[],
+                });
+
+                return Err((Vec::new(), reason));
+            }
         }
 
         let instance = evm::Runtime::new(
@@ -223,6 +302,16 @@ impl<'a, B: AccountStorage> Machine<'a, B> {
     fn apply_create(&mut self, interrupt: CreateInterrupt) -> Result<(), (Vec<u8>, ExitReason)> {
         self.executor.state.enter( false);
 
+        event!(EnterCall {
+            call_type: interrupt.call_type,
+            depth: self.executor.state.call_depth() - 1,
+            from: interrupt.context.caller,
+            to: interrupt.address,
+            value: interrupt.context.apparent_value,
+            gas: interrupt.target_gas,
+            input: &interrupt.init_code,
+        });
+
         if CONFIG.create_increase_nonce {
             self.executor.state.inc_nonce(interrupt.address);
         }
@@ -230,7 +319,16 @@ impl<'a, B: AccountStorage> Machine<'a, B> {
         self.executor.gasometer.record_deploy(&self.executor.state, interrupt.address);
 
         if let Some(transfer) = interrupt.transfer {
-            self.executor.transfer(transfer).map_err(|e| (Vec::new(), e.into()))?;
+            if let Err(error) = self.executor.transfer(transfer) {
+                let reason: ExitReason = error.into();
+                event!(ExitCall {
+                    depth: self.executor.state.call_depth() - 1,
+                    reason: &reason,
+                    return_value: &[],
+                });
+
+                return Err((Vec::new(), reason));
+            }
         }
 
         let valids = evm::Valids::compute(&interrupt.init_code);
@@ -306,6 +404,15 @@ impl<'a, B: AccountStorage> Machine<'a, B> {
 
         emit_exit!(exited_runtime.machine().return_value(), reason);
 
+        // The outermost frame is reported by the `Transact*` events
+        if !self.runtime.is_empty() {
+            event!(ExitCall {
+                depth: self.executor.state.call_depth() - 1,
+                reason: &reason,
+                return_value: &exited_runtime.machine().return_value(),
+            });
+        }
+
         if !reason.is_succeed() {
             self.executor.state.exit_revert();
         }
@@ -341,21 +448,36 @@ impl<'a, B: AccountStorage> Machine<'a, B> {
         let mut steps = 0_u64;
 
         while steps < n {
-            let (steps_executed, apply) = self.run(n - steps);
-            steps += steps_executed;
+            steps += self.run_and_apply(n - steps)?;
+        }
 
-            self.steps_executed += steps_executed;
-            self.executor.gasometer.record_evm_steps(steps_executed);
+        Ok(())
+    }
 
-            match apply {
-                RuntimeApply::Continue => (),
-                RuntimeApply::Call(info) => self.apply_call(info)?,
-                RuntimeApply::Create(info) => self.apply_create(info)?,
-                RuntimeApply::Exit(reason) => self.apply_exit(reason)?,
-            }
+    /// Executes a single opcode of the current call frame.
+    /// Call, create and exit opcodes also enter or leave the call frame.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`execute_n_steps`](Self::execute_n_steps)
+    pub fn execute_step(&mut self) -> Result<(), (Vec<u8>, ExitReason)> {
+        self.run_and_apply(1).map(|_| ())
+    }
+
+    fn run_and_apply(&mut self, max_steps: u64) -> Result<u64, (Vec<u8>, ExitReason)> {
+        let (steps_executed, apply) = self.run(max_steps);
+
+        self.steps_executed += steps_executed;
+        self.executor.gasometer.record_evm_steps(steps_executed);
+
+        match apply {
+            RuntimeApply::Continue => (),
+            RuntimeApply::Call(info) => self.apply_call(info)?,
+            RuntimeApply::Create(info) => self.apply_create(info)?,
+            RuntimeApply::Exit(reason) => self.apply_exit(reason)?,
         }
 
-        Ok(())
+        Ok(steps_executed)
     }
 
     /// Returns number of executed steps.
@@ -370,6 +492,58 @@ impl<'a, B: AccountStorage> Machine<'a, B> {
         self.executor.gasometer.used_gas()
     }
 
+    /// Returns depth of the call stack, zero if execution has not begun or has finished
+    #[must_use]
+    pub fn call_depth(&self) -> usize {
+        self.executor.state.call_depth()
+    }
+
+    fn current_machine(&self) -> Option<&evm::Machine> {
+        self.runtime.last().map(|(runtime, _)| runtime.machine())
+    }
+
+    /// Returns address of the contract executed in the current call frame
+    #[must_use]
+    pub fn contract(&self) -> Option<H160> {
+        self.runtime.last().map(|(runtime, _)| runtime.context().address)
+    }
+
+    /// Returns program counter of the current call frame
+    #[must_use]
+    pub fn pc(&self) -> Option<usize> {
+        self.current_machine()
+            .and_then(|machine| machine.position().as_ref().ok().copied())
+    }
+
+    /// Returns opcode to be executed next in the current call frame
+    #[must_use]
+    pub fn opcode(&self) -> Option<evm::Opcode> {
+        self.current_machine()
+            .and_then(evm::Machine::inspect)
+            .map(|(opcode, _)| opcode)
+    }
+
+    /// Returns stack of the current call frame, the last element is the top of the stack
+    #[must_use]
+    pub fn stack(&self) -> &[H256] {
+        self.current_machine()
+            .map_or(&[], |machine| machine.stack().data())
+    }
+
+    /// Returns memory of the current call frame
+    #[must_use]
+    pub fn memory(&self) -> &[u8] {
+        self.current_machine()
+            .map_or(&[], |machine| machine.memory().data())
+    }
+
+    /// Returns memory size of the current call frame
+    #[must_use]
+    pub fn memory_size(&self) -> usize {
+        self.current_machine()
+            .map_or(0, |machine| machine.memory().len())
+    }
+
     /// Returns gasometer mutable reference
     #[must_use]
     pub fn gasometer_mut(&mut self) -> &mut Gasometer {
@@ -381,3 +555,39 @@ impl<'a, B: AccountStorage> Machine<'a, B> {
         self.executor.state.into_actions()
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use borsh::BorshSerialize;
+    use evm::H160;
+
+    use super::{read_state_version, CreateReason, MACHINE_STATE_TAG, MACHINE_STATE_VERSION};
+
+    #[test]
+    fn test_read_state_version() {
+        let mut data = MACHINE_STATE_TAG.to_vec();
+        data.push(MACHINE_STATE_VERSION);
+        data.extend_from_slice(&[0xFF; 8]);
+
+        let mut buffer: &[u8] = &data;
+        assert_eq!(read_state_version(&mut buffer), Some(MACHINE_STATE_VERSION));
+        assert_eq!(buffer, &[0xFF; 8]);
+
+        data[MACHINE_STATE_TAG.len()] = MACHINE_STATE_VERSION + 1;
+        assert_eq!(read_state_version(&mut &data[..]), Some(MACHINE_STATE_VERSION + 1));
+    }
+
+    #[test]
+    fn test_legacy_state_rejected() {
+        // Legacy layout is the bare Borsh runtime stack, here with 2 frames.
+        // Its first byte equals the current layout version.
+        let legacy = vec![CreateReason::Call, CreateReason::Create(H160::repeat_byte(0x11))]
+            .try_to_vec()
+            .unwrap();
+        assert_eq!(legacy[0], MACHINE_STATE_VERSION);
+
+        assert_eq!(read_state_version(&mut &legacy[..]), None);
+        assert_eq!(read_state_version(&mut &[][..]), None);
+    }
+}
@@ -11,4 +11,5 @@ pub use cache::AccountMeta;
 pub use action::Action;
 pub use state::ExecutorState;
 pub use gasometer::Gasometer;
-pub use machine::Machine;
\ No newline at end of file
+pub use machine::Machine;
+pub use handler::CallType;
\ No newline at end of file
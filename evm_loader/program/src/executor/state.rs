@@ -33,6 +33,7 @@ impl<'a, B: AccountStorage> ExecutorState<'a, B> {
             solana_accounts_partial: BTreeMap::new(),
             block_number: backend.block_number(),
             block_timestamp: backend.block_timestamp(),
+            code_hashes: BTreeMap::new(),
         };
 
         Self {
@@ -288,7 +289,10 @@ impl<'a, B: AccountStorage> ExecutorState<'a, B> {
             }
         }
 
-        known_code.map_or_else(|| self.backend.code_hash(from_address), crate::utils::keccak256_h256)
+        known_code.map_or_else(
+            || self.cache.borrow_mut().get_code_hash_or_insert(*from_address, self.backend),
+            crate::utils::keccak256_h256
+        )
     }
 
     #[must_use]
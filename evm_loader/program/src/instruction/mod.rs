@@ -159,6 +159,20 @@ pub enum EvmInstruction {
 
     /// Converts data account from V1 (HAMT) to V2 (distributed storage).
     ConvertDataAccountFromV1ToV2,
+
+    /// Withdraws NEON tokens earned by an operator from its balance account,
+    /// Ether account at `operator_balance_withdraw::operator_balance_address`.
+    /// Instruction data: amount (u64, token decimals).
+    ///
+    /// Accounts expected by this instruction:
+    ///
+    ///   0. `[signer]` Authorized operator.
+    ///   1. `[writable]` Operator balance Ether account.
+    ///   2. `[writable]` NEON token pool (source) account.
+    ///   3. `[writable]` NEON token destination account.
+    ///   4. `[]` EVM Loader authority account (PDA, seeds = \[b"Deposit"\]).
+    ///   5. `[]` SPL Token program id.
+    OperatorBalanceWithdraw,
 }
 
 impl EvmInstruction {
@@ -193,6 +207,7 @@ impl EvmInstruction {
             27 => Self::ExecuteTrxFromAccountDataIterativeOrContinueNoChainId,
             28 => Self::WriteValueToDistributedStorage,
             29 => Self::ConvertDataAccountFromV1ToV2,
+            30 => Self::OperatorBalanceWithdraw,
 
             _ => return Err(ProgramError::InvalidInstructionData),
         })
@@ -206,6 +221,7 @@ pub mod account_resize;
 pub mod erc20_account_create;
 pub mod neon_tokens_deposit;
 pub mod migrate_account;
+pub mod operator_balance_withdraw;
 pub mod transaction_write_to_holder;
 pub mod transaction_cancel;
 pub mod transaction_execute_from_instruction;
@@ -0,0 +1,153 @@
+use evm::{H160, U256};
+use arrayref::{array_ref};
+use crate::account::{program, token, EthereumAccount, Operator};
+use crate::utils::keccak256_h256_v;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use solana_program::program::invoke_signed;
+use spl_associated_token_account::get_associated_token_address;
+
+struct Accounts<'a> {
+    operator: Operator<'a>,
+    ethereum_account: EthereumAccount<'a>,
+    pool: token::State<'a>,
+    destination: token::State<'a>,
+    authority: &'a AccountInfo<'a>,
+    token_program: program::Token<'a>,
+}
+
+/// Ethereum address collecting the earnings of the operator.
+/// Nobody knows its private key, so the balance can't be spent by Ethereum transactions
+/// and only the operator itself withdraws it. The operator passes the account
+/// as the operator Ether account of transactions to be paid there.
+#[must_use]
+pub fn operator_balance_address(operator: &Pubkey) -> H160 {
+    let digest = keccak256_h256_v(&[b"OperatorBalance", operator.as_ref()]);
+    H160::from_slice(&digest.as_bytes()[12..])
+}
+
+
+pub fn process<'a>(program_id: &'a Pubkey, accounts: &'a [AccountInfo<'a>], instruction: &[u8]) -> ProgramResult {
+    solana_program::msg!("Instruction: Operator Balance Withdraw");
+
+    let mut parsed_accounts = Accounts {
+        operator: Operator::from_account(&accounts[0])?,
+        ethereum_account: EthereumAccount::from_account(program_id, &accounts[1])?,
+        pool: token::State::from_account(&accounts[2])?,
+        destination: token::State::from_account(&accounts[3])?,
+        authority: &accounts[4],
+        token_program: program::Token::from_account(&accounts[5])?,
+    };
+
+    let amount = u64::from_le_bytes(*array_ref![instruction, 0, 8]);
+
+    let bump_seed = validate(program_id, &parsed_accounts)?;
+    execute(&mut parsed_accounts, amount, bump_seed)
+}
+
+fn check_operator_balance(operator: &Pubkey, address: &H160) -> ProgramResult {
+    let expected_address = operator_balance_address(operator);
+    if *address != expected_address {
+        return Err!(ProgramError::InvalidArgument; "Account {} - expected balance account {} of operator {}", address, expected_address, operator);
+    }
+
+    Ok(())
+}
+
+fn validate(program_id: &Pubkey, accounts: &Accounts) -> Result<u8, ProgramError> {
+    check_operator_balance(accounts.operator.key, &accounts.ethereum_account.address)?;
+
+    let (expected_address, bump_seed) = Pubkey::find_program_address(&[b"Deposit"], program_id);
+    if accounts.authority.key != &expected_address {
+        return Err!(ProgramError::InvalidArgument; "Account {} - expected PDA address {}", accounts.authority.key, expected_address);
+    }
+
+    let expected_pool_address = get_associated_token_address(
+        accounts.authority.key,
+        &crate::config::token_mint::id()
+    );
+    if accounts.pool.info.key != &expected_pool_address {
+        return Err!(ProgramError::InvalidArgument; "Account {} - expected Neon Token Pool {}", accounts.pool.info.key, expected_pool_address);
+    }
+
+    if accounts.destination.mint != crate::config::token_mint::id() {
+        return Err!(ProgramError::InvalidArgument; "Account {} - expected Neon Token account", accounts.destination.info.key);
+    }
+
+    accounts.ethereum_account.check_blocked(true)?;
+
+    Ok(bump_seed)
+}
+
+fn execute(accounts: &mut Accounts, amount: u64, bump_seed: u8) -> ProgramResult {
+    assert!(crate::config::token_mint::decimals() <= 18);
+    let additional_decimals: u32 = (18 - crate::config::token_mint::decimals()).into();
+
+    let withdrawal = U256::from(amount) * U256::from(10_u64.pow(additional_decimals));
+    if accounts.ethereum_account.balance < withdrawal {
+        return Err!(ProgramError::InsufficientFunds; "Account {} - insufficient funds, required = {}", accounts.ethereum_account.address, withdrawal);
+    }
+
+    accounts.ethereum_account.balance -= withdrawal;
+
+    {
+        let signers_seeds: &[&[&[u8]]] = &[&[b"Deposit", &[bump_seed]]];
+
+        let instruction = spl_token::instruction::transfer(
+            accounts.token_program.key,
+            accounts.pool.info.key,
+            accounts.destination.info.key,
+            accounts.authority.key,
+            &[],
+            amount
+        )?;
+
+        let account_infos: &[AccountInfo] = &[
+            accounts.pool.info.clone(),
+            accounts.destination.info.clone(),
+            accounts.authority.clone(),
+            accounts.token_program.clone(),
+        ];
+
+        invoke_signed(&instruction, account_infos, signers_seeds)?;
+    }
+
+    info_print!("Withdrew {} from {} to {}", withdrawal, accounts.ethereum_account.address, accounts.destination.info.key);
+
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use evm::H160;
+    use solana_program::pubkey::Pubkey;
+
+    use super::{check_operator_balance, operator_balance_address};
+
+    #[test]
+    fn test_operator_withdraws_own_balance() {
+        let operator = Pubkey::new_unique();
+        let address = operator_balance_address(&operator);
+
+        assert!(check_operator_balance(&operator, &address).is_ok());
+        assert_eq!(operator_balance_address(&operator), address);
+    }
+
+    #[test]
+    fn test_operator_cannot_withdraw_other_accounts() {
+        let operator = Pubkey::new_unique();
+        let other_operator = Pubkey::new_unique();
+
+        let other_balance = operator_balance_address(&other_operator);
+        assert_ne!(operator_balance_address(&operator), other_balance);
+        assert!(check_operator_balance(&operator, &other_balance).is_err());
+
+        let user = H160::repeat_byte(0x42);
+        assert!(check_operator_balance(&operator, &user).is_err());
+    }
+}
@@ -14,6 +14,10 @@ use evm::U256;
 pub fn process<'a>(program_id: &'a Pubkey, accounts: &'a [AccountInfo<'a>], instruction: &[u8]) -> ProgramResult {
     solana_program::msg!("Instruction: Begin or Continue Transaction from Account Without ChainId");
 
+    if crate::config::NO_CHAINID_REJECTED {
+        return Err!(ProgramError::InvalidInstructionData; "Transactions without chain id are not supported, sign the transaction with chain id {}", crate::config::CHAIN_ID);
+    }
+
     let treasury_index = u32::from_le_bytes(*array_ref![instruction, 0, 4]);
     let step_count = u64::from_le_bytes(*array_ref![instruction, 4, 8]);
 
@@ -46,6 +50,7 @@ pub fn process<'a>(program_id: &'a Pubkey, accounts: &'a [AccountInfo<'a>], inst
         if trx.chain_id.is_some() {
             return Err!(ProgramError::InvalidArgument; "Expected transaction without chain id");
         }
+        info_print!("Deprecated: transactions without chain id will be rejected in future releases");
 
         let mut storage = State::new(program_id, storage_info, &accounts, caller, &trx, &signature)?;
         storage.gas_limit = storage.gas_limit.saturating_mul(U256::from(crate::config::GAS_LIMIT_MULTIPLIER_NO_CHAINID));
@@ -0,0 +1,808 @@
+//! Typed constructors of EVM Loader instructions for off-chain clients.
+//! Account lists follow the order expected by the processors in `crate::instruction`.
+
+use evm::{H160, U256};
+use solana_program::{
+    incinerator,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+    sysvar,
+};
+use spl_associated_token_account::get_associated_token_address;
+
+use crate::account::ACCOUNT_SEED_VERSION;
+use crate::config::token_mint;
+use crate::instruction::operator_balance_withdraw;
+
+
+/// Accounts of the operator paying for an Ethereum transaction execution
+#[derive(Debug, Clone, Copy)]
+pub struct OperatorAccounts {
+    /// Operator signing the Solana transaction
+    pub operator: Pubkey,
+    /// Treasury (collateral pool) account receiving the fee
+    pub treasury: Pubkey,
+    /// Index of the treasury account, part of its seed
+    pub treasury_index: u32,
+    /// Solana account of the operator Ethereum account receiving the gas payment
+    pub operator_ether_account: Pubkey,
+}
+
+impl OperatorAccounts {
+    fn account_metas(&self, program_id: &Pubkey) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.operator, true),
+            AccountMeta::new(self.treasury, false),
+            AccountMeta::new(self.operator_ether_account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*program_id, false),
+        ]
+    }
+}
+
+
+/// Solana address of the Ethereum account and its bump seed
+#[must_use]
+pub fn ether_account_address(program_id: &Pubkey, address: &H160) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&[ACCOUNT_SEED_VERSION], address.as_bytes()], program_id)
+}
+
+/// Solana address of the Ethereum contract storage slot outside of the contract account.
+/// `generation` is the contract account generation, see `AccountStorage::generation`.
+#[must_use]
+pub fn storage_address(program_id: &Pubkey, address: &H160, generation: u32, index: &U256) -> Pubkey {
+    let mut index_bytes = [0_u8; 32];
+    index.to_little_endian(&mut index_bytes);
+
+    let seeds: &[&[u8]] = &[&[ACCOUNT_SEED_VERSION], b"ContractStorage", address.as_bytes(), &generation.to_le_bytes(), &index_bytes];
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
+/// Program authority of the NEON token pool (PDA, seeds = \[b"Deposit"\])
+#[must_use]
+pub fn deposit_authority(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"Deposit"], program_id).0
+}
+
+/// NEON token pool, associated token account of the deposit authority
+#[must_use]
+pub fn neon_token_pool(program_id: &Pubkey) -> Pubkey {
+    get_associated_token_address(&deposit_authority(program_id), &token_mint::id())
+}
+
+
+fn step_data(tag: u8, treasury_index: u32, step_count: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(1 + 4 + 8);
+    data.push(tag);
+    data.extend_from_slice(&treasury_index.to_le_bytes());
+    data.extend_from_slice(&step_count.to_le_bytes());
+    data
+}
+
+fn signed_transaction_data(mut data: Vec<u8>, caller: &H160, signature: &[u8; 65], unsigned_msg: &[u8]) -> Vec<u8> {
+    data.reserve(20 + 65 + unsigned_msg.len());
+    data.extend_from_slice(caller.as_bytes());
+    data.extend_from_slice(signature);
+    data.extend_from_slice(unsigned_msg);
+    data
+}
+
+fn execution_instruction(
+    program_id: &Pubkey,
+    data: Vec<u8>,
+    mut account_metas: Vec<AccountMeta>,
+    operator: &OperatorAccounts,
+    accounts: &[AccountMeta],
+) -> Instruction {
+    account_metas.extend(operator.account_metas(program_id));
+    account_metas.extend_from_slice(accounts);
+
+    Instruction::new_with_bytes(*program_id, &data, account_metas)
+}
+
+
+/// `EvmInstruction::CreateAccountV02`.
+/// `code_account` is required for contracts and must be created with seed by the `funding` account.
+#[must_use]
+pub fn create_account(program_id: &Pubkey, funding: &Pubkey, address: &H160, code_account: Option<&Pubkey>) -> Instruction {
+    let (solana_address, bump_seed) = ether_account_address(program_id, address);
+
+    let mut data = Vec::with_capacity(1 + 20 + 1);
+    data.push(24_u8);
+    data.extend_from_slice(address.as_bytes());
+    data.push(bump_seed);
+
+    let mut account_metas = vec![
+        AccountMeta::new(*funding, true),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new(solana_address, false),
+    ];
+    if let Some(code_account) = code_account {
+        account_metas.push(AccountMeta::new(*code_account, false));
+    }
+
+    Instruction::new_with_bytes(*program_id, &data, account_metas)
+}
+
+/// `EvmInstruction::DeleteHolderOrStorageAccount`.
+/// `deleted` must be created with `seed` by the `operator`, which receives its lamports.
+#[must_use]
+pub fn delete_holder_or_storage(program_id: &Pubkey, deleted: &Pubkey, operator: &Pubkey, seed: &str) -> Instruction {
+    let mut data = vec![16_u8];
+    data.extend_from_slice(seed.as_bytes());
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*deleted, false),
+            AccountMeta::new(*operator, true),
+        ],
+    )
+}
+
+/// `EvmInstruction::ResizeContractAccount`.
+/// `code_account` is `None` for an Ethereum account without code.
+#[must_use]
+pub fn resize_contract_account(
+    program_id: &Pubkey,
+    ether_account: &Pubkey,
+    code_account: Option<&Pubkey>,
+    new_code_account: &Pubkey,
+    operator: &Pubkey,
+    seed: &str,
+) -> Instruction {
+    let mut data = vec![17_u8];
+    data.extend_from_slice(seed.as_bytes());
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*ether_account, false),
+            AccountMeta::new(code_account.copied().unwrap_or_default(), false),
+            AccountMeta::new(*new_code_account, false),
+            AccountMeta::new(*operator, true),
+        ],
+    )
+}
+
+/// `EvmInstruction::ERC20CreateTokenAccount`
+#[must_use]
+pub fn erc20_create_token_account(
+    program_id: &Pubkey,
+    operator: &Pubkey,
+    wallet: &H160,
+    contract: &H160,
+    mint: &Pubkey,
+) -> Instruction {
+    let seeds: &[&[u8]] = &[
+        &[ACCOUNT_SEED_VERSION], b"ERC20Balance", &mint.to_bytes(), contract.as_bytes(), wallet.as_bytes()
+    ];
+    let (token_account, _) = Pubkey::find_program_address(seeds, program_id);
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &[15_u8],
+        vec![
+            AccountMeta::new(*operator, true),
+            AccountMeta::new(token_account, false),
+            AccountMeta::new_readonly(ether_account_address(program_id, wallet).0, false),
+            AccountMeta::new_readonly(ether_account_address(program_id, contract).0, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+    )
+}
+
+/// `EvmInstruction::Deposit`.
+/// Requires previously executed SPL-Token.Approve of the amount to the `deposit_authority`.
+#[must_use]
+pub fn deposit(program_id: &Pubkey, source: &Pubkey, ether_account: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[25_u8],
+        vec![
+            AccountMeta::new(*source, false),
+            AccountMeta::new(neon_token_pool(program_id), false),
+            AccountMeta::new(*ether_account, false),
+            AccountMeta::new_readonly(deposit_authority(program_id), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+/// `EvmInstruction::MigrateAccount`
+#[must_use]
+pub fn migrate_account(program_id: &Pubkey, operator: &Pubkey, ether_account: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[26_u8],
+        vec![
+            AccountMeta::new(*operator, true),
+            AccountMeta::new(*ether_account, false),
+            AccountMeta::new(get_associated_token_address(ether_account, &token_mint::id()), false),
+            AccountMeta::new(neon_token_pool(program_id), false),
+            AccountMeta::new_readonly(deposit_authority(program_id), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+/// `EvmInstruction::UpdateValidsTable`
+#[must_use]
+pub fn update_valids_table(program_id: &Pubkey, code_account: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[23_u8],
+        vec![AccountMeta::new(*code_account, false)],
+    )
+}
+
+/// `EvmInstruction::WriteHolder`.
+/// Writes `bytes` at `offset` of the holder created by the `operator` with seed of `holder_id`.
+#[must_use]
+pub fn write_holder(program_id: &Pubkey, holder: &Pubkey, operator: &Pubkey, holder_id: u64, offset: u32, bytes: &[u8]) -> Instruction {
+    let mut data = Vec::with_capacity(1 + 8 + 4 + 8 + bytes.len());
+    data.push(18_u8);
+    data.extend_from_slice(&holder_id.to_le_bytes());
+    data.extend_from_slice(&offset.to_le_bytes());
+    data.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+    data.extend_from_slice(bytes);
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*holder, false),
+            AccountMeta::new(*operator, true),
+        ],
+    )
+}
+
+/// `EvmInstruction::CancelWithNonce`.
+/// `nonce` is the current nonce of the transaction caller,
+/// `accounts` are the accounts used by the canceled transaction.
+#[must_use]
+pub fn cancel_with_nonce(program_id: &Pubkey, storage: &Pubkey, operator: &Pubkey, nonce: u64, accounts: &[AccountMeta]) -> Instruction {
+    let mut data = vec![21_u8];
+    data.extend_from_slice(&nonce.to_le_bytes());
+
+    let mut account_metas = vec![
+        AccountMeta::new(*storage, false),
+        AccountMeta::new(*operator, true),
+        AccountMeta::new(incinerator::id(), false),
+    ];
+    account_metas.extend_from_slice(accounts);
+
+    Instruction::new_with_bytes(*program_id, &data, account_metas)
+}
+
+/// `EvmInstruction::CallFromRawEthereumTX`.
+/// Executes the signed Ethereum transaction in a single Solana instruction.
+#[must_use]
+pub fn call_from_raw_ethereum_tx(
+    program_id: &Pubkey,
+    operator: &OperatorAccounts,
+    caller: &H160,
+    signature: &[u8; 65],
+    unsigned_msg: &[u8],
+    accounts: &[AccountMeta],
+) -> Instruction {
+    let mut data = Vec::with_capacity(1 + 4);
+    data.push(5_u8);
+    data.extend_from_slice(&operator.treasury_index.to_le_bytes());
+
+    execution_instruction(
+        program_id,
+        signed_transaction_data(data, caller, signature, unsigned_msg),
+        vec![AccountMeta::new_readonly(sysvar::instructions::id(), false)],
+        operator,
+        accounts,
+    )
+}
+
+/// `EvmInstruction::PartialCallFromRawEthereumTXv02`.
+/// Begins iterative execution of the signed Ethereum transaction in the `storage` account.
+#[must_use]
+#[allow(clippy::too_many_arguments)]
+pub fn partial_call_from_raw_ethereum_tx(
+    program_id: &Pubkey,
+    storage: &Pubkey,
+    operator: &OperatorAccounts,
+    step_count: u64,
+    caller: &H160,
+    signature: &[u8; 65],
+    unsigned_msg: &[u8],
+    accounts: &[AccountMeta],
+) -> Instruction {
+    execution_instruction(
+        program_id,
+        signed_transaction_data(step_data(19_u8, operator.treasury_index, step_count), caller, signature, unsigned_msg),
+        vec![
+            AccountMeta::new(*storage, false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+        operator,
+        accounts,
+    )
+}
+
+/// `EvmInstruction::PartialCallOrContinueFromRawEthereumTX`.
+/// Begins or continues iterative execution of the signed Ethereum transaction in the `storage` account.
+#[must_use]
+#[allow(clippy::too_many_arguments)]
+pub fn partial_call_or_continue_from_raw_ethereum_tx(
+    program_id: &Pubkey,
+    storage: &Pubkey,
+    operator: &OperatorAccounts,
+    step_count: u64,
+    caller: &H160,
+    signature: &[u8; 65],
+    unsigned_msg: &[u8],
+    accounts: &[AccountMeta],
+) -> Instruction {
+    execution_instruction(
+        program_id,
+        signed_transaction_data(step_data(13_u8, operator.treasury_index, step_count), caller, signature, unsigned_msg),
+        vec![
+            AccountMeta::new(*storage, false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+        operator,
+        accounts,
+    )
+}
+
+/// `EvmInstruction::ContinueV02`.
+/// Continues iterative execution of the transaction in the `storage` account.
+#[must_use]
+pub fn continue_v02(
+    program_id: &Pubkey,
+    storage: &Pubkey,
+    operator: &OperatorAccounts,
+    step_count: u64,
+    accounts: &[AccountMeta],
+) -> Instruction {
+    execution_instruction(
+        program_id,
+        step_data(20_u8, operator.treasury_index, step_count),
+        vec![AccountMeta::new(*storage, false)],
+        operator,
+        accounts,
+    )
+}
+
+/// `EvmInstruction::ExecuteTrxFromAccountDataIterativeV02`.
+/// Begins iterative execution of the transaction written to the `holder` account.
+#[must_use]
+pub fn execute_trx_from_account_data_iterative(
+    program_id: &Pubkey,
+    holder: &Pubkey,
+    storage: &Pubkey,
+    operator: &OperatorAccounts,
+    step_count: u64,
+    accounts: &[AccountMeta],
+) -> Instruction {
+    execution_instruction(
+        program_id,
+        step_data(22_u8, operator.treasury_index, step_count),
+        vec![
+            AccountMeta::new(*holder, false),
+            AccountMeta::new(*storage, false),
+        ],
+        operator,
+        accounts,
+    )
+}
+
+/// `EvmInstruction::ExecuteTrxFromAccountDataIterativeOrContinue`.
+/// Begins or continues iterative execution of the transaction written to the `holder` account.
+#[must_use]
+pub fn execute_trx_from_account_data_iterative_or_continue(
+    program_id: &Pubkey,
+    holder: &Pubkey,
+    storage: &Pubkey,
+    operator: &OperatorAccounts,
+    step_count: u64,
+    accounts: &[AccountMeta],
+) -> Instruction {
+    execution_instruction(
+        program_id,
+        step_data(14_u8, operator.treasury_index, step_count),
+        vec![
+            AccountMeta::new(*holder, false),
+            AccountMeta::new(*storage, false),
+        ],
+        operator,
+        accounts,
+    )
+}
+
+/// `EvmInstruction::ExecuteTrxFromAccountDataIterativeOrContinueNoChainId`.
+/// Same as `execute_trx_from_account_data_iterative_or_continue`, but for transactions without chain id.
+#[must_use]
+pub fn execute_trx_from_account_data_iterative_or_continue_no_chain_id(
+    program_id: &Pubkey,
+    holder: &Pubkey,
+    storage: &Pubkey,
+    operator: &OperatorAccounts,
+    step_count: u64,
+    accounts: &[AccountMeta],
+) -> Instruction {
+    execution_instruction(
+        program_id,
+        step_data(27_u8, operator.treasury_index, step_count),
+        vec![
+            AccountMeta::new(*holder, false),
+            AccountMeta::new(*storage, false),
+        ],
+        operator,
+        accounts,
+    )
+}
+
+/// `EvmInstruction::WriteValueToDistributedStorage`.
+/// Moves the storage slot `index` of the contract at `address` out of the contract account.
+/// V1 contracts are converted before any redeployment, so the storage belongs to generation 0.
+#[must_use]
+pub fn write_value_to_distributed_storage(
+    program_id: &Pubkey,
+    operator: &Pubkey,
+    address: &H160,
+    index: &U256,
+    value: &U256,
+) -> Instruction {
+    let mut data = vec![28_u8; 1 + 32 + 32];
+    index.to_big_endian(&mut data[1..33]);
+    value.to_big_endian(&mut data[33..]);
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new(*operator, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(ether_account_address(program_id, address).0, false),
+            AccountMeta::new(storage_address(program_id, address, 0, index), false),
+        ],
+    )
+}
+
+/// `EvmInstruction::ConvertDataAccountFromV1ToV2`
+#[must_use]
+pub fn convert_data_account_from_v1_to_v2(program_id: &Pubkey, funding: &Pubkey, ether_account: &Pubkey) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[29_u8],
+        vec![
+            AccountMeta::new(*funding, true),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new(*ether_account, false),
+        ],
+    )
+}
+
+/// `EvmInstruction::OperatorBalanceWithdraw`.
+/// Withdraws `amount` from the balance account of the `operator` to the `destination` token account.
+#[must_use]
+pub fn operator_balance_withdraw(
+    program_id: &Pubkey,
+    operator: &Pubkey,
+    destination: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let balance_address = operator_balance_withdraw::operator_balance_address(operator);
+
+    let mut data = Vec::with_capacity(1 + 8);
+    data.push(30_u8);
+    data.extend_from_slice(&amount.to_le_bytes());
+
+    Instruction::new_with_bytes(
+        *program_id,
+        &data,
+        vec![
+            AccountMeta::new_readonly(*operator, true),
+            AccountMeta::new(ether_account_address(program_id, &balance_address).0, false),
+            AccountMeta::new(neon_token_pool(program_id), false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(deposit_authority(program_id), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+    )
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use evm::{H160, U256};
+    use solana_program::{
+        incinerator,
+        instruction::{AccountMeta, Instruction},
+        pubkey::Pubkey,
+        system_program,
+        sysvar,
+    };
+
+    use crate::instruction::EvmInstruction;
+
+    use super::*;
+
+    fn parse(instruction: &Instruction) -> EvmInstruction {
+        EvmInstruction::parse(&instruction.data[0]).unwrap()
+    }
+
+    fn keys(instruction: &Instruction) -> Vec<Pubkey> {
+        instruction.accounts.iter().map(|meta| meta.pubkey).collect()
+    }
+
+    fn operator_accounts() -> OperatorAccounts {
+        OperatorAccounts {
+            operator: Pubkey::new_unique(),
+            treasury: Pubkey::new_unique(),
+            treasury_index: 7,
+            operator_ether_account: Pubkey::new_unique(),
+        }
+    }
+
+    /// Operator accounts start at `offset`, remaining accounts follow them
+    fn assert_execution_accounts(
+        program_id: &Pubkey,
+        instruction: &Instruction,
+        offset: usize,
+        operator: &OperatorAccounts,
+        remaining: &[AccountMeta],
+    ) {
+        let keys = keys(instruction);
+        assert_eq!(keys[offset], operator.operator);
+        assert!(instruction.accounts[offset].is_signer);
+        assert_eq!(keys[offset + 1], operator.treasury);
+        assert_eq!(keys[offset + 2], operator.operator_ether_account);
+        assert_eq!(keys[offset + 3], system_program::id());
+        assert_eq!(keys[offset + 4], *program_id);
+        assert_eq!(&instruction.accounts[offset + 5..], remaining);
+    }
+
+    /// Treasury index and step count, as read by the iterative processors
+    fn assert_step_data(instruction: &Instruction, operator: &OperatorAccounts, step_count: u64) {
+        let data = &instruction.data[1..];
+        assert_eq!(u32::from_le_bytes(data[0..4].try_into().unwrap()), operator.treasury_index);
+        assert_eq!(u64::from_le_bytes(data[4..12].try_into().unwrap()), step_count);
+    }
+
+    #[test]
+    fn test_create_account() {
+        let program_id = Pubkey::new_unique();
+        let funding = Pubkey::new_unique();
+        let code_account = Pubkey::new_unique();
+        let address = H160::repeat_byte(0x11);
+        let (solana_address, bump_seed) = ether_account_address(&program_id, &address);
+
+        let instruction = create_account(&program_id, &funding, &address, None);
+        assert_eq!(parse(&instruction), EvmInstruction::CreateAccountV02);
+        assert_eq!(&instruction.data[1..21], address.as_bytes());
+        assert_eq!(instruction.data[21], bump_seed);
+        assert_eq!(keys(&instruction), vec![funding, system_program::id(), solana_address]);
+        assert!(instruction.accounts[0].is_signer);
+
+        let instruction = create_account(&program_id, &funding, &address, Some(&code_account));
+        assert_eq!(keys(&instruction), vec![funding, system_program::id(), solana_address, code_account]);
+    }
+
+    #[test]
+    fn test_account_instructions() {
+        let program_id = Pubkey::new_unique();
+        let operator = Pubkey::new_unique();
+        let account = Pubkey::new_unique();
+
+        let instruction = delete_holder_or_storage(&program_id, &account, &operator, "seed");
+        assert_eq!(parse(&instruction), EvmInstruction::DeleteHolderOrStorageAccount);
+        assert_eq!(&instruction.data[1..], b"seed");
+        assert_eq!(keys(&instruction), vec![account, operator]);
+
+        let new_code_account = Pubkey::new_unique();
+        let instruction = resize_contract_account(&program_id, &account, None, &new_code_account, &operator, "seed");
+        assert_eq!(parse(&instruction), EvmInstruction::ResizeContractAccount);
+        assert_eq!(keys(&instruction), vec![account, Pubkey::default(), new_code_account, operator]);
+
+        let instruction = update_valids_table(&program_id, &account);
+        assert_eq!(parse(&instruction), EvmInstruction::UpdateValidsTable);
+        assert_eq!(keys(&instruction), vec![account]);
+
+        let instruction = migrate_account(&program_id, &operator, &account);
+        assert_eq!(parse(&instruction), EvmInstruction::MigrateAccount);
+        assert_eq!(keys(&instruction)[..2], [operator, account]);
+        assert_eq!(keys(&instruction)[3..], [neon_token_pool(&program_id), deposit_authority(&program_id), spl_token::id()]);
+
+        let instruction = convert_data_account_from_v1_to_v2(&program_id, &operator, &account);
+        assert_eq!(parse(&instruction), EvmInstruction::ConvertDataAccountFromV1ToV2);
+        assert_eq!(keys(&instruction), vec![operator, system_program::id(), account]);
+    }
+
+    #[test]
+    fn test_erc20_create_token_account() {
+        let program_id = Pubkey::new_unique();
+        let operator = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let wallet = H160::repeat_byte(0x11);
+        let contract = H160::repeat_byte(0x22);
+
+        let instruction = erc20_create_token_account(&program_id, &operator, &wallet, &contract, &mint);
+        assert_eq!(parse(&instruction), EvmInstruction::ERC20CreateTokenAccount);
+
+        let keys = keys(&instruction);
+        assert_eq!(keys[0], operator);
+        assert_eq!(keys[2], ether_account_address(&program_id, &wallet).0);
+        assert_eq!(keys[3], ether_account_address(&program_id, &contract).0);
+        assert_eq!(keys[4..], [mint, system_program::id(), spl_token::id(), sysvar::rent::id()]);
+    }
+
+    #[test]
+    fn test_neon_token_instructions() {
+        let program_id = Pubkey::new_unique();
+        let operator = Pubkey::new_unique();
+        let source = Pubkey::new_unique();
+        let ether_account = Pubkey::new_unique();
+
+        let instruction = deposit(&program_id, &source, &ether_account);
+        assert_eq!(parse(&instruction), EvmInstruction::Deposit);
+        assert_eq!(
+            keys(&instruction),
+            vec![source, neon_token_pool(&program_id), ether_account, deposit_authority(&program_id), spl_token::id()]
+        );
+
+        let instruction = operator_balance_withdraw(&program_id, &operator, &source, 1_000);
+        assert_eq!(parse(&instruction), EvmInstruction::OperatorBalanceWithdraw);
+        assert_eq!(u64::from_le_bytes(instruction.data[1..9].try_into().unwrap()), 1_000);
+
+        let balance_address = operator_balance_withdraw::operator_balance_address(&operator);
+        assert_eq!(
+            keys(&instruction),
+            vec![
+                operator,
+                ether_account_address(&program_id, &balance_address).0,
+                neon_token_pool(&program_id),
+                source,
+                deposit_authority(&program_id),
+                spl_token::id(),
+            ]
+        );
+        assert!(instruction.accounts[0].is_signer);
+    }
+
+    #[test]
+    fn test_write_holder() {
+        let program_id = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        let operator = Pubkey::new_unique();
+
+        let instruction = write_holder(&program_id, &holder, &operator, 42, 229, &[1, 2, 3]);
+        assert_eq!(parse(&instruction), EvmInstruction::WriteHolder);
+
+        let data = &instruction.data[1..];
+        assert_eq!(u64::from_le_bytes(data[0..8].try_into().unwrap()), 42);
+        assert_eq!(u32::from_le_bytes(data[8..12].try_into().unwrap()), 229);
+        assert_eq!(u64::from_le_bytes(data[12..20].try_into().unwrap()), 3);
+        assert_eq!(&data[20..], &[1_u8, 2, 3]);
+        assert_eq!(keys(&instruction), vec![holder, operator]);
+    }
+
+    #[test]
+    fn test_cancel_with_nonce() {
+        let program_id = Pubkey::new_unique();
+        let storage = Pubkey::new_unique();
+        let operator = Pubkey::new_unique();
+        let remaining = vec![AccountMeta::new(Pubkey::new_unique(), false)];
+
+        let instruction = cancel_with_nonce(&program_id, &storage, &operator, 5, &remaining);
+        assert_eq!(parse(&instruction), EvmInstruction::CancelWithNonce);
+        assert_eq!(u64::from_le_bytes(instruction.data[1..9].try_into().unwrap()), 5);
+        assert_eq!(keys(&instruction)[..3], [storage, operator, incinerator::id()]);
+        assert_eq!(&instruction.accounts[3..], remaining.as_slice());
+    }
+
+    #[test]
+    fn test_transaction_from_instruction() {
+        let program_id = Pubkey::new_unique();
+        let storage = Pubkey::new_unique();
+        let operator = operator_accounts();
+        let remaining = vec![AccountMeta::new(Pubkey::new_unique(), false)];
+        let caller = H160::repeat_byte(0x33);
+        let signature = [0x44_u8; 65];
+        let unsigned_msg = [0x55_u8; 10];
+
+        let instruction = call_from_raw_ethereum_tx(&program_id, &operator, &caller, &signature, &unsigned_msg, &remaining);
+        assert_eq!(parse(&instruction), EvmInstruction::CallFromRawEthereumTX);
+        let data = &instruction.data[1..];
+        assert_eq!(u32::from_le_bytes(data[0..4].try_into().unwrap()), operator.treasury_index);
+        assert_eq!(&data[4..24], caller.as_bytes());
+        assert_eq!(&data[24..89], &signature[..]);
+        assert_eq!(&data[89..], &unsigned_msg[..]);
+        assert_eq!(instruction.accounts[0].pubkey, sysvar::instructions::id());
+        assert_execution_accounts(&program_id, &instruction, 1, &operator, &remaining);
+
+        let builders: [(fn(&Pubkey, &Pubkey, &OperatorAccounts, u64, &H160, &[u8; 65], &[u8], &[AccountMeta]) -> Instruction, EvmInstruction); 2] = [
+            (partial_call_from_raw_ethereum_tx, EvmInstruction::PartialCallFromRawEthereumTXv02),
+            (partial_call_or_continue_from_raw_ethereum_tx, EvmInstruction::PartialCallOrContinueFromRawEthereumTX),
+        ];
+        for (builder, expected) in builders {
+            let instruction = builder(&program_id, &storage, &operator, 100, &caller, &signature, &unsigned_msg, &remaining);
+            assert_eq!(parse(&instruction), expected);
+            assert_step_data(&instruction, &operator, 100);
+
+            let data = &instruction.data[1 + 4 + 8..];
+            assert_eq!(&data[0..20], caller.as_bytes());
+            assert_eq!(&data[20..85], &signature[..]);
+            assert_eq!(&data[85..], &unsigned_msg[..]);
+
+            assert_eq!(keys(&instruction)[..2], [storage, sysvar::instructions::id()]);
+            assert_execution_accounts(&program_id, &instruction, 2, &operator, &remaining);
+        }
+    }
+
+    #[test]
+    fn test_transaction_from_account() {
+        let program_id = Pubkey::new_unique();
+        let holder = Pubkey::new_unique();
+        let storage = Pubkey::new_unique();
+        let operator = operator_accounts();
+        let remaining = vec![AccountMeta::new_readonly(Pubkey::new_unique(), false)];
+
+        let builders: [(fn(&Pubkey, &Pubkey, &Pubkey, &OperatorAccounts, u64, &[AccountMeta]) -> Instruction, EvmInstruction); 3] = [
+            (execute_trx_from_account_data_iterative, EvmInstruction::ExecuteTrxFromAccountDataIterativeV02),
+            (execute_trx_from_account_data_iterative_or_continue, EvmInstruction::ExecuteTrxFromAccountDataIterativeOrContinue),
+            (execute_trx_from_account_data_iterative_or_continue_no_chain_id, EvmInstruction::ExecuteTrxFromAccountDataIterativeOrContinueNoChainId),
+        ];
+        for (builder, expected) in builders {
+            let instruction = builder(&program_id, &holder, &storage, &operator, 100, &remaining);
+            assert_eq!(parse(&instruction), expected);
+            assert_eq!(instruction.data.len(), 1 + 4 + 8);
+            assert_step_data(&instruction, &operator, 100);
+            assert_eq!(keys(&instruction)[..2], [holder, storage]);
+            assert_execution_accounts(&program_id, &instruction, 2, &operator, &remaining);
+        }
+
+        let instruction = continue_v02(&program_id, &storage, &operator, 400, &remaining);
+        assert_eq!(parse(&instruction), EvmInstruction::ContinueV02);
+        assert_step_data(&instruction, &operator, 400);
+        assert_eq!(instruction.accounts[0].pubkey, storage);
+        assert_execution_accounts(&program_id, &instruction, 1, &operator, &remaining);
+    }
+
+    #[test]
+    fn test_write_value_to_distributed_storage() {
+        let program_id = Pubkey::new_unique();
+        let operator = Pubkey::new_unique();
+        let address = H160::repeat_byte(0x11);
+        let index = U256::from(100_u64);
+        let value = U256::from(200_u64);
+
+        let instruction = write_value_to_distributed_storage(&program_id, &operator, &address, &index, &value);
+        assert_eq!(parse(&instruction), EvmInstruction::WriteValueToDistributedStorage);
+        assert_eq!(U256::from(&instruction.data[1..33]), index);
+        assert_eq!(U256::from(&instruction.data[33..65]), value);
+        assert_eq!(
+            keys(&instruction),
+            vec![
+                operator,
+                system_program::id(),
+                ether_account_address(&program_id, &address).0,
+                storage_address(&program_id, &address, 0, &index),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_storage_address_depends_on_generation() {
+        let program_id = Pubkey::new_unique();
+        let address = H160::repeat_byte(0x11);
+        let index = U256::from(100_u64);
+
+        assert_ne!(storage_address(&program_id, &address, 0, &index), storage_address(&program_id, &address, 1, &index));
+    }
+}
@@ -21,6 +21,8 @@ pub mod executor;
 pub mod external_programs;
 pub mod hamt;
 pub mod instruction;
+#[cfg(not(target_arch = "bpf"))]
+pub mod instruction_builder;
 pub mod precompile;
 pub mod state_account;
 pub mod transaction;
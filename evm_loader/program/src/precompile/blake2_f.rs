@@ -1,14 +1,27 @@
 use std::convert::Infallible;
 
-use evm::{Capture, ExitReason};
+use evm::{Capture, ExitError, ExitReason};
 
+const BLAKE2_F_ARG_LEN: usize = 213;
+
+
+/// Number of rounds requested by the input, the cost of the call is proportional to it (EIP-152)
+#[must_use]
+pub fn rounds(input: &[u8]) -> u32 {
+    if input.len() != BLAKE2_F_ARG_LEN {
+        return 0;
+    }
+
+    let mut rounds_arr: [u8; 4] = Default::default();
+    rounds_arr.copy_from_slice(&input[..4]);
+    u32::from_be_bytes(rounds_arr)
+}
 
 #[must_use]
 #[allow(clippy::too_many_lines)]
 pub fn blake2_f(
     input: &[u8]
 ) -> Capture<(ExitReason, Vec<u8>), Infallible> {
-    const BLAKE2_F_ARG_LEN: usize = 213;
     debug_print!("blake2F");
 
     let compress = |h: &mut [u64; 8], m: [u64; 16], t: [u64; 2], f: bool, rounds: usize| {
@@ -73,14 +86,12 @@ pub fn blake2_f(
     };
 
     if input.len() != BLAKE2_F_ARG_LEN {
-        // return Err(ExitError::Other("input length for Blake2 F precompile should be exactly 213 bytes".into()));
-        return Capture::Exit((ExitReason::Succeed(evm::ExitSucceed::Returned), Vec::new()));
+        let error = ExitError::Other("input length for Blake2 F precompile should be exactly 213 bytes".into());
+        return Capture::Exit((ExitReason::Error(error), Vec::new()));
     }
 
-    let mut rounds_arr: [u8; 4] = Default::default();
-    let (rounds_buf, input) = input.split_at(4);
-    rounds_arr.copy_from_slice(rounds_buf);
-    let rounds: u32 = u32::from_be_bytes(rounds_arr);
+    let rounds = rounds(input);
+    let input = &input[4..];
 
     // we use from_le_bytes below to effectively swap byte order to LE if architecture is BE
 
@@ -119,8 +130,8 @@ pub fn blake2_f(
     } else if input[0] == 0 {
         false
     } else {
-        // return Err(ExitError::Other("incorrect final block indicator flag".into()))
-        return Capture::Exit((ExitReason::Succeed(evm::ExitSucceed::Returned), Vec::new()));
+        let error = ExitError::Other("incorrect final block indicator flag".into());
+        return Capture::Exit((ExitReason::Error(error), Vec::new()));
     };
 
     compress(&mut h, m, [t_0, t_1], f, rounds as usize);
@@ -137,3 +148,72 @@ pub fn blake2_f(
         output_buf.to_vec(),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use evm::{Capture, ExitReason};
+
+    use super::{blake2_f, rounds};
+
+    /// EIP-152 test vectors 4-7 share everything but the rounds and the final block flag
+    fn input(rounds: u32, f: u8) -> Vec<u8> {
+        let mut input = rounds.to_be_bytes().to_vec();
+        input.extend(hex::decode(
+            "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b"
+        ).unwrap());
+        let mut m = [0_u8; 128];
+        m[..3].copy_from_slice(b"abc");
+        input.extend_from_slice(&m);
+        input.extend_from_slice(&3_u64.to_le_bytes());
+        input.extend_from_slice(&0_u64.to_le_bytes());
+        input.push(f);
+        input
+    }
+
+    fn call(input: &[u8]) -> (ExitReason, Vec<u8>) {
+        match blake2_f(input) {
+            Capture::Exit(result) => result,
+            Capture::Trap(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_blake2_f_vectors() {
+        let vectors = [
+            (0, 1, "08c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d282e6ad7f520e511f6c3e2b8c68059b9442be0454267ce079217e1319cde05b"),
+            (12, 1, "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"),
+            (12, 0, "75ab69d3190a562c51aef8d88f1c2775876944407270c42c9844252c26d2875298743e7f6d5ea2f2d3e8d226039cd31b4e426ac4f2d3d666a610c2116fde4735"),
+            (1, 1, "b63a380cb2897d521994a85234ee2c181b5f844d2c624c002677e9703449d2fba551b3a8333bcdf5f2f7e08993d53923de3d64fcc68c034e717b9293fed7a421"),
+        ];
+        for (vector_rounds, f, expected) in vectors {
+            let input = input(vector_rounds, f);
+            assert_eq!(rounds(&input), vector_rounds);
+
+            let (exit_reason, output) = call(&input);
+            assert!(exit_reason.is_succeed(), "rounds {}, f {}", vector_rounds, f);
+            assert_eq!(hex::encode(output), expected, "rounds {}, f {}", vector_rounds, f);
+        }
+    }
+
+    #[test]
+    fn test_blake2_f_invalid_input() {
+        let valid = input(12, 1);
+
+        // EIP-152 test vectors 0-3: empty, too short, too long, invalid final block flag
+        let mut invalid_flag = valid.clone();
+        invalid_flag[212] = 2;
+        let inputs = [
+            Vec::new(),
+            valid[1..].to_vec(),
+            [valid.as_slice(), &[0]].concat(),
+            invalid_flag,
+        ];
+        for input in inputs {
+            let (exit_reason, output) = call(&input);
+            assert!(exit_reason.is_error(), "input len {}", input.len());
+            assert!(output.is_empty());
+        }
+
+        assert_eq!(rounds(&valid[1..]), 0);
+    }
+}
@@ -4,7 +4,7 @@ use arrayref::{array_ref, array_refs};
 use evm::{Capture, ExitReason, H160, U256, ExitRevert, ExitSucceed, H256};
 use solana_program::{pubkey::Pubkey, program_error::ProgramError, program_pack::Pack, rent::Rent, sysvar::Sysvar, system_instruction};
 
-use crate::{account_storage::AccountStorage, executor::{ExecutorState, Gasometer}, account::ACCOUNT_SEED_VERSION};
+use crate::{account_storage::AccountStorage, executor::{ExecutorState, Gasometer}, account::{ACCOUNT_SEED_VERSION, token::token_2022}};
 
 const ERC20_METHOD_DECIMALS_ID: &[u8; 4]       = &[0x31, 0x3c, 0xe5, 0x67];
 const ERC20_METHOD_TOTAL_SUPPLY_ID: &[u8; 4]   = &[0x18, 0x16, 0x0d, 0xdd];
@@ -386,6 +386,10 @@ fn erc20_approve_solana<B: AccountStorage>(
 
 fn get_token_mint<B: AccountStorage>(state: &ExecutorState<B>, address: Pubkey) -> Result<spl_token::state::Mint, ProgramError> {
     let account = state.external_account(address)?;
+    if token_2022::check_id(&account.owner) {
+        return Err!(ProgramError::IncorrectProgramId; "Account {} - Token-2022 is not supported", address);
+    }
+
     if !spl_token::check_id(&account.owner) {
         return Ok(spl_token::state::Mint::default())
     }
@@ -395,6 +399,10 @@ fn get_token_mint<B: AccountStorage>(state: &ExecutorState<B>, address: Pubkey)
 
 fn get_token_account<B: AccountStorage>(state: &ExecutorState<B>, address: Pubkey) -> Result<spl_token::state::Account, ProgramError> {
     let account = state.external_account(address)?;
+    if token_2022::check_id(&account.owner) {
+        return Err!(ProgramError::IncorrectProgramId; "Account {} - Token-2022 is not supported", address);
+    }
+
     if !spl_token::check_id(&account.owner) {
         return Ok(spl_token::state::Account::default())
     }
@@ -0,0 +1,298 @@
+use std::convert::{Infallible, TryInto};
+
+use evm::{Capture, ExitReason, U256, ExitSucceed, ExitRevert};
+use solana_program::{pubkey::Pubkey, program_error::ProgramError};
+
+use crate::{
+    account_storage::AccountStorage,
+    executor::{ExecutorState, Gasometer},
+};
+
+// [0xf7, 0xb6, 0x37, 0xbb] : "isInitialized(bytes32)",
+// [0x69, 0x1f, 0x34, 0x31] : "name(bytes32)",
+// [0x6b, 0xaa, 0x03, 0x30] : "symbol(bytes32)",
+// [0x9e, 0xd1, 0x9d, 0xdb] : "uri(bytes32)",
+// [0xb1, 0xc1, 0x06, 0x65] : "updateAuthority(bytes32)"
+
+pub mod metadata_program {
+    solana_program::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+}
+
+const METADATA_KEY_V1: u8 = 4;
+
+/// Every call derives the metadata address and reads its account,
+/// which costs about as much compute as this number of EVM steps
+const METAPLEX_CALL_EVM_STEPS: u64 = 100;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Method {
+    IsInitialized,
+    Name,
+    Symbol,
+    Uri,
+    UpdateAuthority,
+}
+
+impl Method {
+    fn from_selector(selector: &[u8]) -> Option<Self> {
+        match selector {
+            [0xf7, 0xb6, 0x37, 0xbb] => Some(Self::IsInitialized),   // isInitialized(bytes32 mint)
+            [0x69, 0x1f, 0x34, 0x31] => Some(Self::Name),            // name(bytes32 mint)
+            [0x6b, 0xaa, 0x03, 0x30] => Some(Self::Symbol),          // symbol(bytes32 mint)
+            [0x9e, 0xd1, 0x9d, 0xdb] => Some(Self::Uri),             // uri(bytes32 mint)
+            [0xb1, 0xc1, 0x06, 0x65] => Some(Self::UpdateAuthority), // updateAuthority(bytes32 mint)
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Metadata {
+    update_authority: Pubkey,
+    name: String,
+    symbol: String,
+    uri: String,
+}
+
+#[must_use]
+pub fn metaplex<B: AccountStorage>(
+    input: &[u8],
+    context: &evm::Context,
+    state: &mut ExecutorState<B>,
+    gasometer: &mut Gasometer
+) -> Capture<(ExitReason, Vec<u8>), Infallible>
+{
+    if !context.apparent_value.is_zero() {
+        return Capture::Exit((ExitRevert::Reverted.into(), vec![]))
+    }
+
+    if input.len() < 4 + 32 {
+        return Capture::Exit((ExitRevert::Reverted.into(), vec![]))
+    }
+
+    let (selector, input) = input.split_at(4);
+    let method = match Method::from_selector(selector) {
+        Some(method) => method,
+        None => {
+            debug_print!("metaplex UNKNOWN {:?}", selector);
+            return Capture::Exit((ExitReason::Fatal(evm::ExitFatal::NotSupported), vec![]))
+        }
+    };
+
+    gasometer.record_evm_steps(METAPLEX_CALL_EVM_STEPS);
+
+    let mint = Pubkey::new_from_array(*arrayref::array_ref![input, 0, 32]);
+    let result = match method {
+        Method::IsInitialized => is_initialized(state, &mint),
+        method => read_metadata(state, &mint).map(|metadata| encode_metadata(method, &metadata)),
+    };
+
+    match result {
+        Ok(value) => Capture::Exit((ExitSucceed::Returned.into(), value)),
+        Err(_) => Capture::Exit((ExitRevert::Reverted.into(), vec![])),
+    }
+}
+
+
+fn metadata_address(mint: &Pubkey) -> Pubkey {
+    let seeds: &[&[u8]] = &[b"metadata", metadata_program::ID.as_ref(), mint.as_ref()];
+    let (address, _) = Pubkey::find_program_address(seeds, &metadata_program::ID);
+
+    address
+}
+
+fn is_initialized<B: AccountStorage>(
+    state: &mut ExecutorState<B>,
+    mint: &Pubkey,
+) -> Result<Vec<u8>, ProgramError>
+{
+    let account = state.external_account(metadata_address(mint))?;
+    let initialized = metadata_program::check_id(&account.owner) && account.data.first() == Some(&METADATA_KEY_V1);
+
+    Ok(abi_bool(initialized))
+}
+
+fn read_metadata<B: AccountStorage>(
+    state: &mut ExecutorState<B>,
+    mint: &Pubkey,
+) -> Result<Metadata, ProgramError>
+{
+    let address = metadata_address(mint);
+    let account = state.external_account(address)?;
+    if !metadata_program::check_id(&account.owner) {
+        return Err!(ProgramError::IllegalOwner; "Account {} - is not metadata program owned", address);
+    }
+
+    parse_metadata(&address, mint, &account.data)
+}
+
+fn parse_metadata(address: &Pubkey, mint: &Pubkey, data: &[u8]) -> Result<Metadata, ProgramError> {
+    if data.len() < 1 + 32 + 32 || data[0] != METADATA_KEY_V1 {
+        return Err!(ProgramError::InvalidAccountData; "Account {} - is not token metadata", address);
+    }
+
+    let update_authority = Pubkey::new(&data[1..33]);
+    let metadata_mint = Pubkey::new(&data[33..65]);
+    if metadata_mint != *mint {
+        return Err!(ProgramError::InvalidAccountData; "Account {} - metadata mint mismatch {}", address, metadata_mint);
+    }
+
+    let mut offset = 65;
+    let name = read_string(data, &mut offset)?;
+    let symbol = read_string(data, &mut offset)?;
+    let uri = read_string(data, &mut offset)?;
+
+    Ok(Metadata { update_authority, name, symbol, uri })
+}
+
+/// Borsh string: u32 LE length followed by utf8 bytes, padded with zeroes by metaplex
+fn read_string(data: &[u8], offset: &mut usize) -> Result<String, ProgramError> {
+    let len_end = offset.checked_add(4).ok_or(ProgramError::InvalidAccountData)?;
+    let len = data.get(*offset..len_end).ok_or(ProgramError::InvalidAccountData)?;
+    let len: usize = u32::from_le_bytes(len.try_into().unwrap()).try_into().unwrap();
+
+    let end = len_end.checked_add(len).ok_or(ProgramError::InvalidAccountData)?;
+    let bytes = data.get(len_end..end).ok_or(ProgramError::InvalidAccountData)?;
+    *offset = end;
+
+    let value = std::str::from_utf8(bytes).map_err(|_| ProgramError::InvalidAccountData)?;
+    Ok(value.trim_end_matches('\0').to_string())
+}
+
+fn encode_metadata(method: Method, metadata: &Metadata) -> Vec<u8> {
+    match method {
+        Method::IsInitialized => abi_bool(true),
+        Method::Name => abi_string(&metadata.name),
+        Method::Symbol => abi_string(&metadata.symbol),
+        Method::Uri => abi_string(&metadata.uri),
+        Method::UpdateAuthority => metadata.update_authority.to_bytes().to_vec(),
+    }
+}
+
+fn abi_bool(value: bool) -> Vec<u8> {
+    let mut result = vec![0_u8; 32];
+    result[31] = value.into();
+
+    result
+}
+
+fn abi_string(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let padded_len = (bytes.len() + 31) / 32 * 32;
+
+    let mut result = vec![0_u8; 64 + padded_len];
+    U256::from(32).into_big_endian_fast(&mut result[0..32]);
+    U256::from(bytes.len()).into_big_endian_fast(&mut result[32..64]);
+    result[64..64 + bytes.len()].copy_from_slice(bytes);
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use evm::U256;
+    use solana_program::pubkey::Pubkey;
+
+    use crate::utils::keccak256_digest;
+
+    use super::{abi_bool, abi_string, encode_metadata, parse_metadata, Metadata, Method, METADATA_KEY_V1};
+
+    fn borsh_string(value: &str, padded_len: usize) -> Vec<u8> {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.resize(padded_len, 0);
+
+        let mut result = u32::try_from(bytes.len()).unwrap().to_le_bytes().to_vec();
+        result.extend(bytes);
+        result
+    }
+
+    fn metadata_account_data(update_authority: &Pubkey, mint: &Pubkey, metadata: &Metadata) -> Vec<u8> {
+        let mut data = vec![METADATA_KEY_V1];
+        data.extend_from_slice(update_authority.as_ref());
+        data.extend_from_slice(mint.as_ref());
+        data.extend(borsh_string(&metadata.name, 32));
+        data.extend(borsh_string(&metadata.symbol, 10));
+        data.extend(borsh_string(&metadata.uri, 200));
+        data
+    }
+
+    /// Decodes ABI `string` return value
+    fn decode_string(output: &[u8]) -> String {
+        assert_eq!(output.len() % 32, 0);
+        assert_eq!(U256::from_big_endian(&output[0..32]), U256::from(32));
+
+        let len = U256::from_big_endian(&output[32..64]).as_usize();
+        assert_eq!(output.len(), 64 + (len + 31) / 32 * 32);
+        assert!(output[64 + len..].iter().all(|b| *b == 0));
+
+        String::from_utf8(output[64..64 + len].to_vec()).unwrap()
+    }
+
+    fn metadata() -> Metadata {
+        Metadata {
+            update_authority: Pubkey::new_unique(),
+            name: "Neon EVM test token with a name longer than a word".to_string(),
+            symbol: "NEON".to_string(),
+            uri: "https://example.com/token.json".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_selectors() {
+        let methods = [
+            ("isInitialized(bytes32)", Method::IsInitialized),
+            ("name(bytes32)", Method::Name),
+            ("symbol(bytes32)", Method::Symbol),
+            ("uri(bytes32)", Method::Uri),
+            ("updateAuthority(bytes32)", Method::UpdateAuthority),
+        ];
+        for (signature, method) in methods {
+            let selector = &keccak256_digest(signature.as_bytes())[..4];
+            assert_eq!(Method::from_selector(selector), Some(method), "{}", signature);
+        }
+
+        let selector = &keccak256_digest(b"decimals(bytes32)")[..4];
+        assert_eq!(Method::from_selector(selector), None);
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let address = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let expected = metadata();
+
+        let data = metadata_account_data(&expected.update_authority, &mint, &expected);
+        assert_eq!(parse_metadata(&address, &mint, &data).unwrap(), expected);
+
+        let other_mint = Pubkey::new_unique();
+        assert!(parse_metadata(&address, &other_mint, &data).is_err());
+        assert!(parse_metadata(&address, &mint, &data[..100]).is_err());
+
+        let mut data = data;
+        data[0] = METADATA_KEY_V1 + 1;
+        assert!(parse_metadata(&address, &mint, &data).is_err());
+    }
+
+    #[test]
+    fn test_encode_metadata() {
+        let metadata = metadata();
+
+        assert_eq!(decode_string(&encode_metadata(Method::Name, &metadata)), metadata.name);
+        assert_eq!(decode_string(&encode_metadata(Method::Symbol, &metadata)), metadata.symbol);
+        assert_eq!(decode_string(&encode_metadata(Method::Uri, &metadata)), metadata.uri);
+        assert_eq!(encode_metadata(Method::UpdateAuthority, &metadata), metadata.update_authority.to_bytes().to_vec());
+    }
+
+    #[test]
+    fn test_abi_encoding() {
+        assert_eq!(decode_string(&abi_string("")), "");
+        assert_eq!(abi_string("").len(), 64);
+        assert_eq!(abi_string(&"a".repeat(32)).len(), 64 + 32);
+        assert_eq!(abi_string(&"a".repeat(33)).len(), 64 + 64);
+
+        assert_eq!(U256::from_big_endian(&abi_bool(true)), U256::one());
+        assert_eq!(U256::from_big_endian(&abi_bool(false)), U256::zero());
+    }
+}
@@ -8,7 +8,7 @@ use solana_program::{
 
 use crate::{
     account_storage::AccountStorage,
-    executor::{ExecutorState, Gasometer, OwnedAccountInfo}, account::ACCOUNT_SEED_VERSION,
+    executor::{ExecutorState, Gasometer, OwnedAccountInfo}, account::{ACCOUNT_SEED_VERSION, token::token_2022},
 };
 
 // [0xa9, 0xc1, 0x58, 0x06] : "approve(bytes32,bytes32,uint64)",
@@ -585,6 +585,10 @@ fn get_account<B: AccountStorage>(
 ) -> Result<Vec<u8>, ProgramError>
 {
     let account = state.external_account(account)?;
+    if token_2022::check_id(&account.owner) {
+        return Err!(ProgramError::IncorrectProgramId; "Account {} - Token-2022 is not supported", account.key);
+    }
+
     let token = if spl_token::check_id(&account.owner) {
         spl_token::state::Account::unpack_unchecked(&account.data)?
     } else {
@@ -615,6 +619,10 @@ fn get_mint<B: AccountStorage>(
 ) -> Result<Vec<u8>, ProgramError>
 {
     let account = state.external_account(account)?;
+    if token_2022::check_id(&account.owner) {
+        return Err!(ProgramError::IncorrectProgramId; "Account {} - Token-2022 is not supported", account.key);
+    }
+
     let mint = if spl_token::check_id(&account.owner) {
         spl_token::state::Mint::unpack_unchecked(&account.data)?
     } else {
@@ -3,6 +3,9 @@
 use evm::Context;
 use evm::{H160, H256, U256};
 use evm_runtime::{CreateScheme, ExitReason, Transfer};
+use solana_program::{instruction::AccountMeta, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::executor::CallType;
 
 environmental::environmental!(listener: dyn EventListener + 'static);
 
@@ -61,6 +64,45 @@ pub enum Event<'a> {
         /// Return value
         return_value: &'a [u8],
     },
+    /// Entering an inner call frame, emitted for precompiles as well
+    EnterCall {
+        /// Opcode that created the frame
+        call_type: CallType,
+        /// Depth of the frame, the transaction frame has depth 0.
+        /// One less than `ExecutorState::call_depth` inside the frame.
+        depth: usize,
+        /// Caller address as seen by the callee
+        from: H160,
+        /// Called code address or address of the created account
+        to: H160,
+        /// Value visible to the callee
+        value: U256,
+        /// Target gas
+        gas: Option<u64>,
+        /// Input data or init code
+        input: &'a [u8],
+    },
+    /// Leaving an inner call frame, emitted for every `EnterCall`
+    ExitCall {
+        /// Depth of the frame, same as in `EnterCall`
+        depth: usize,
+        /// Exit reason
+        reason: &'a ExitReason,
+        /// Return value
+        return_value: &'a [u8],
+    },
+    /// Solana program call requested by a precompile.
+    /// Emitted when the instruction is invoked, after the EVM finishes and the transaction succeeds.
+    ExternalCall {
+        /// Called Solana program
+        program_id: Pubkey,
+        /// Instruction accounts
+        accounts: &'a [AccountMeta],
+        /// Instruction data
+        data: &'a [u8],
+        /// Result of the invocation
+        result: &'a Result<(), ProgramError>,
+    },
     /// Transactional Call event
     TransactCall {
         /// Caller account address
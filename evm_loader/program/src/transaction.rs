@@ -9,7 +9,7 @@ use solana_program::{
 };
 use std::convert::{Into, TryFrom};
 use crate::account_storage::ProgramAccountStorage;
-use crate::utils::{keccak256_digest};
+use crate::utils::{address_from_public_key, keccak256_digest};
 
 #[repr(packed)]
 #[allow(dead_code)]
@@ -152,8 +152,7 @@ pub fn verify_tx_signature(signature: &[u8; 65], unsigned_trx: &[u8]) -> Result<
     let public_key = secp256k1_recover(&digest, signature[64], &signature[0..64])
         .map_err(|e| E!(ProgramError::MissingRequiredSignature; "Secp256k1 Error={:?}", e))?;
 
-    let address = keccak256_digest(&public_key.to_bytes());
-    let address = H160::from_slice(&address[12..32]);
+    let address = address_from_public_key(&public_key.to_bytes());
 
     Ok(address)
 }
@@ -178,10 +177,7 @@ pub fn check_ethereum_transaction(
     }
 
     let contract_address: H160 = transaction.to.unwrap_or_else(|| {
-        let mut stream = rlp::RlpStream::new_list(2);
-        stream.append(recovered_address);
-        stream.append(&U256::from(transaction.nonce));
-        crate::utils::keccak256_h256(&stream.out()).into()
+        crate::utils::create_address(recovered_address, U256::from(transaction.nonce))
     });
     let contract_account = account_storage.ethereum_account(&contract_address)
         .ok_or_else(|| E!(ProgramError::InvalidArgument; "Account {} - target must be initialized account", contract_address))?;
@@ -1,6 +1,6 @@
 //! `EVMLoader` helper functions
 
-use evm::{H256, U256};
+use evm::{H160, H256, U256};
 use solana_program::keccak::{hash, hashv};
 
 /// Get Keccak256 hash as `H256`
@@ -21,6 +21,38 @@ pub fn keccak256_digest(data: &[u8]) -> Vec<u8> {
     hash(data).to_bytes().to_vec()
 }
 
+/// Ethereum address of the uncompressed public key (without 0x04 prefix)
+#[must_use]
+pub fn address_from_public_key(public_key: &[u8]) -> H160 {
+    let digest = hash(public_key).to_bytes();
+    H160::from_slice(&digest[12..32])
+}
+
+/// Address of a contract created by `CREATE`: `keccak256(rlp([caller, nonce]))[12..]`.
+/// RLP is laid out on the stack and hashed with a single `hashv` syscall.
+#[must_use]
+pub fn create_address(caller: &H160, nonce: U256) -> H160 {
+    let mut nonce_bytes = [0_u8; 32];
+    nonce.to_big_endian(&mut nonce_bytes);
+    let nonce_len = 32 - nonce_bytes.iter().take_while(|b| **b == 0).count();
+    let nonce_bytes = &nonce_bytes[32 - nonce_len..];
+
+    // Nonce below 0x80 is encoded by itself, zero is an empty string
+    #[allow(clippy::cast_possible_truncation)] // nonce_len <= 32
+    let nonce_prefix = 0x80 + nonce_len as u8;
+    let nonce_prefix: &[u8] = match nonce_bytes {
+        [byte] if *byte < 0x80 => &[],
+        _ => &[nonce_prefix],
+    };
+
+    // Payload never exceeds 55 bytes: (1 + 20) + (1 + 32)
+    #[allow(clippy::cast_possible_truncation)]
+    let list_prefix = 0xc0 + (1 + 20 + nonce_prefix.len() + nonce_len) as u8;
+
+    let digest = hashv(&[&[list_prefix, 0x80 + 20], caller.as_bytes(), nonce_prefix, nonce_bytes]).to_bytes();
+    H160::from_slice(&digest[12..32])
+}
+
 /// Convert U256 to H256
 #[must_use]
 pub fn u256_to_h256(value: U256) -> H256 {
@@ -28,3 +60,36 @@ pub fn u256_to_h256(value: U256) -> H256 {
     value.to_big_endian(&mut v);
     H256::from_slice(&v)
 }
+
+#[cfg(test)]
+mod tests {
+    use evm::{H160, U256};
+
+    use super::{create_address, keccak256_h256};
+
+    fn create_address_rlp(caller: &H160, nonce: U256) -> H160 {
+        let mut stream = rlp::RlpStream::new_list(2);
+        stream.append(caller);
+        stream.append(&nonce);
+        keccak256_h256(&stream.out()).into()
+    }
+
+    #[test]
+    fn test_create_address() {
+        let caller = H160::from_low_u64_be(0x1234_5678_9abc_def0);
+
+        let nonces = [
+            U256::zero(),
+            U256::one(),
+            U256::from(0x7f),
+            U256::from(0x80),
+            U256::from(0xff),
+            U256::from(0x0100),
+            U256::from(u64::MAX),
+            U256::MAX,
+        ];
+        for nonce in nonces {
+            assert_eq!(create_address(&caller, nonce), create_address_rlp(&caller, nonce), "nonce {}", nonce);
+        }
+    }
+}
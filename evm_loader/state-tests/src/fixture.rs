@@ -0,0 +1,105 @@
+use std::{collections::BTreeMap, str::FromStr};
+
+use evm::{H160, H256, U256};
+use serde::{Deserialize, Deserializer, de::Error};
+
+/// `ethereum/tests` `GeneralStateTests` file: test name -> test
+pub type StateTests = BTreeMap<String, StateTest>;
+
+
+#[derive(Deserialize)]
+pub struct StateTest {
+    pub env: Env,
+    pub pre: BTreeMap<Address, PreAccount>,
+    pub transaction: Transaction,
+    /// Fork name -> expected results for each transaction variant
+    pub post: BTreeMap<String, Vec<PostState>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Env {
+    pub current_coinbase: Address,
+    pub current_number: Uint,
+    pub current_timestamp: Uint,
+    pub current_gas_limit: Uint,
+    pub previous_hash: Hash,
+}
+
+#[derive(Deserialize)]
+pub struct PreAccount {
+    pub balance: Uint,
+    pub nonce: Uint,
+    pub code: Bytes,
+    pub storage: BTreeMap<Uint, Uint>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    pub data: Vec<Bytes>,
+    pub gas_limit: Vec<Uint>,
+    pub gas_price: Option<Uint>,
+    pub nonce: Uint,
+    pub secret_key: Hash,
+    /// Present in newer fixtures, derived from `secret_key` otherwise
+    pub sender: Option<Address>,
+    #[serde(deserialize_with = "deserialize_optional_address")]
+    pub to: Option<H160>,
+    pub value: Vec<Uint>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PostState {
+    pub hash: Hash,
+    pub logs: Hash,
+    pub indexes: Indexes,
+    pub expect_exception: Option<String>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct Indexes {
+    pub data: usize,
+    pub gas: usize,
+    pub value: usize,
+}
+
+
+fn clean_hex(value: &str) -> &str {
+    value.strip_prefix("0x").unwrap_or(value)
+}
+
+macro_rules! hex_type {
+    ($name:ident, $inner:ty, $parse:expr) => {
+        #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+        pub struct $name(pub $inner);
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let value = String::deserialize(deserializer)?;
+                let parse: fn(&str) -> Result<$inner, String> = $parse;
+                parse(clean_hex(&value))
+                    .map($name)
+                    .map_err(|e| D::Error::custom(format!("invalid value {}: {}", value, e)))
+            }
+        }
+    };
+}
+
+hex_type!(Uint, U256, |s| if s.is_empty() { Ok(U256::zero()) } else { U256::from_str_radix(s, 16).map_err(|e| format!("{:?}", e)) });
+hex_type!(Address, H160, |s| H160::from_str(s).map_err(|e| e.to_string()));
+hex_type!(Hash, H256, |s| H256::from_str(s).map_err(|e| e.to_string()));
+hex_type!(Bytes, Vec<u8>, |s| hex::decode(s).map_err(|e| e.to_string()));
+
+fn deserialize_optional_address<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<H160>, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    let value = clean_hex(&value);
+    if value.is_empty() {
+        return Ok(None);
+    }
+
+    H160::from_str(value)
+        .map(Some)
+        .map_err(|e| D::Error::custom(format!("invalid address {}: {}", value, e)))
+}
@@ -0,0 +1,284 @@
+//! # Neon EVM state tests
+//!
+//! Runs `ethereum/tests` `GeneralStateTests` fixtures through the Neon EVM `Machine`
+//! with an in-memory account storage and compares the resulting state root and logs hash
+//! with the expected ones.
+//!
+//! Neon EVM charges gas by its own rules, so the state root of fixtures with non-zero gas price
+//! is expected to differ by sender and coinbase balances. The logs hash does not depend on gas.
+#![deny(warnings)]
+#![deny(clippy::all, clippy::pedantic)]
+#![allow(clippy::module_name_repetitions)]
+
+mod fixture;
+mod runner;
+mod storage;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+};
+
+use clap::{crate_description, crate_name, crate_version, App, Arg, ArgMatches};
+use evm::H160;
+use log::{error, LevelFilter};
+use solana_sdk::{program_stubs::SyscallStubs, sysvar::rent::Rent};
+
+use crate::{
+    fixture::{PostState, StateTest, StateTests},
+    storage::MemoryAccount,
+};
+
+
+struct Stubs;
+
+impl SyscallStubs for Stubs {
+    fn sol_get_rent_sysvar(&self, pointer: *mut u8) -> u64 {
+        unsafe {
+            #[allow(clippy::cast_ptr_alignment)]
+            let rent = pointer.cast::<Rent>();
+            *rent = Rent::default();
+        }
+
+        0
+    }
+}
+
+
+#[derive(Default)]
+struct Summary {
+    passed: usize,
+    logs_only: usize,
+    failed: usize,
+    errors: usize,
+    skipped: usize,
+}
+
+struct Options {
+    fork: String,
+    filter: Option<String>,
+    chain_id: u64,
+    verbose: bool,
+}
+
+
+fn collect_files(path: &Path, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort();
+
+        for entry in entries {
+            collect_files(&entry, files)?;
+        }
+    } else if path.extension().map_or(false, |ext| ext == "json") {
+        files.push(path.to_path_buf());
+    }
+
+    Ok(())
+}
+
+fn load_file(path: &Path) -> Result<StateTests, String> {
+    let data = fs::read(path).map_err(|e| format!("{}: failed to read fixture: {}", path.display(), e))?;
+    serde_json::from_slice(&data).map_err(|e| format!("{}: failed to parse fixture: {}", path.display(), e))
+}
+
+fn run_file(path: &Path, options: &Options, summary: &mut Summary) {
+    let tests = match load_file(path) {
+        Ok(tests) => tests,
+        Err(e) => {
+            error!("{}", e);
+            summary.errors += 1;
+            return;
+        }
+    };
+
+    for (name, test) in &tests {
+        if options.filter.as_ref().map_or(false, |filter| !name.contains(filter.as_str())) {
+            continue;
+        }
+
+        if let Some(posts) = test.post.get(&options.fork) {
+            for (index, post) in posts.iter().enumerate() {
+                let case = format!("{}::{}[{}]", name, options.fork, index);
+                run_case(&case, test, post, options, summary);
+            }
+        } else {
+            summary.skipped += 1;
+        }
+    }
+}
+
+fn run_case(case: &str, test: &StateTest, post: &PostState, options: &Options, summary: &mut Summary) {
+    if post.expect_exception.is_some() {
+        summary.skipped += 1;
+        return;
+    }
+
+    let outcome = match runner::run(test, post, options.chain_id) {
+        Ok(outcome) => outcome,
+        Err(e) => {
+            println!("ERROR {}: {}", case, e);
+            summary.errors += 1;
+            return;
+        }
+    };
+
+    let (state_match, logs_match) = outcome.matches(post);
+    if state_match && logs_match {
+        println!("PASS  {}", case);
+        summary.passed += 1;
+        return;
+    }
+
+    if logs_match {
+        summary.logs_only += 1;
+    } else {
+        summary.failed += 1;
+    }
+
+    let mut diff = serde_json::json!({
+        "exit_reason": outcome.exit_reason.map(|reason| format!("{:?}", reason)),
+        "used_gas": outcome.used_gas.to_string(),
+        "state_root": {
+            "match": state_match,
+            "expected": format!("{:?}", post.hash.0),
+            "actual": format!("{:?}", outcome.state_root),
+        },
+        "logs": {
+            "match": logs_match,
+            "expected": format!("{:?}", post.logs.0),
+            "actual": format!("{:?}", outcome.logs_hash),
+        },
+    });
+
+    if options.verbose {
+        diff["accounts"] = accounts_json(&outcome.accounts).into();
+    }
+
+    println!("FAIL  {} {}", case, diff);
+}
+
+fn accounts_json(accounts: &[(H160, MemoryAccount)]) -> serde_json::Map<String, serde_json::Value> {
+    accounts.iter()
+        .map(|(address, account)| {
+            let storage: serde_json::Map<String, serde_json::Value> = account.storage.iter()
+                .map(|(key, value)| (format!("{:#x}", key), format!("{:#x}", value).into()))
+                .collect();
+
+            (format!("{:?}", address), serde_json::json!({
+                "nonce": account.nonce.to_string(),
+                "balance": account.balance.to_string(),
+                "code": hex::encode(&account.code),
+                "storage": storage,
+            }))
+        })
+        .collect()
+}
+
+fn parse_options(app_matches: &ArgMatches<'_>) -> Result<Options, String> {
+    let chain_id = app_matches.value_of("chain_id").unwrap();
+
+    Ok(Options {
+        fork: app_matches.value_of("fork").unwrap().to_string(),
+        filter: app_matches.value_of("filter").map(ToString::to_string),
+        chain_id: chain_id.parse().map_err(|e| format!("Invalid chain_id {}: {}", chain_id, e))?,
+        verbose: app_matches.is_present("verbose"),
+    })
+}
+
+/// Runs all fixtures found at the `path` argument
+fn run(app_matches: &ArgMatches<'_>) -> Result<Summary, String> {
+    let options = parse_options(app_matches)?;
+
+    let mut files = Vec::new();
+    let path = Path::new(app_matches.value_of("path").unwrap());
+    collect_files(path, &mut files).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+    let mut summary = Summary::default();
+    for file in &files {
+        run_file(file, &options, &mut summary);
+    }
+
+    println!(
+        "{} files: {} passed, {} logs only, {} failed, {} errors, {} skipped",
+        files.len(), summary.passed, summary.logs_only, summary.failed, summary.errors, summary.skipped
+    );
+
+    Ok(summary)
+}
+
+
+fn main() {
+    let app_matches = App::new(crate_name!())
+        .about(crate_description!())
+        .version(crate_version!())
+        .arg(
+            Arg::with_name("path")
+                .index(1)
+                .value_name("PATH")
+                .takes_value(true)
+                .required(true)
+                .help("Fixture file or directory with GeneralStateTests fixtures"),
+        )
+        .arg(
+            Arg::with_name("fork")
+                .long("fork")
+                .value_name("FORK")
+                .takes_value(true)
+                .default_value("Istanbul")
+                .help("Fork name of the expected results"),
+        )
+        .arg(
+            Arg::with_name("filter")
+                .long("filter")
+                .value_name("SUBSTRING")
+                .takes_value(true)
+                .help("Run only tests with name containing substring"),
+        )
+        .arg(
+            Arg::with_name("chain_id")
+                .long("chain_id")
+                .value_name("CHAIN_ID")
+                .takes_value(true)
+                .default_value("1")
+                .help("Chain ID used by fixtures"),
+        )
+        .arg(
+            Arg::with_name("verbose")
+                .long("verbose")
+                .short("v")
+                .takes_value(false)
+                .help("Print resulting accounts of failed tests"),
+        )
+        .arg(
+            Arg::with_name("loglevel")
+                .long("loglevel")
+                .value_name("LOG_LEVEL")
+                .takes_value(true)
+                .default_value("warn")
+                .possible_values(&["off", "error", "warn", "info", "debug", "trace"])
+                .help("Logging level"),
+        )
+        .get_matches();
+
+    let loglevel: LevelFilter = app_matches.value_of("loglevel").unwrap().parse().unwrap();
+    fern::Dispatch::new()
+        .level(loglevel)
+        .chain(std::io::stderr())
+        .apply()
+        .unwrap();
+
+    solana_sdk::program_stubs::set_syscall_stubs(Box::new(Stubs));
+
+    match run(&app_matches) {
+        Ok(summary) if summary.failed + summary.errors == 0 => {},
+        Ok(_) => exit(1),
+        Err(e) => {
+            error!("{}", e);
+            exit(1);
+        }
+    }
+}
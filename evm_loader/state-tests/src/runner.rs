@@ -0,0 +1,136 @@
+use evm::{H160, H256, U256, ExitReason};
+use keccak_hasher::KeccakHasher;
+use log::debug;
+use rlp::RlpStream;
+
+use evm_loader::{executor::Machine, utils::keccak256_h256};
+
+use crate::{
+    fixture::{StateTest, PostState},
+    storage::{MemoryAccountStorage, MemoryAccount, Log},
+};
+
+
+pub struct Outcome {
+    pub exit_reason: Option<ExitReason>,
+    pub used_gas: U256,
+    pub state_root: H256,
+    pub logs_hash: H256,
+    pub accounts: Vec<(H160, MemoryAccount)>,
+}
+
+impl Outcome {
+    pub fn matches(&self, post: &PostState) -> (bool, bool) {
+        (self.state_root == post.hash.0, self.logs_hash == post.logs.0)
+    }
+}
+
+
+/// Sender of the fixture transaction
+pub fn sender(test: &StateTest) -> Result<H160, String> {
+    if let Some(sender) = &test.transaction.sender {
+        return Ok(sender.0);
+    }
+
+    let secret_key = libsecp256k1::SecretKey::parse(test.transaction.secret_key.0.as_fixed_bytes())
+        .map_err(|e| format!("invalid secret key: {:?}", e))?;
+    let public_key = libsecp256k1::PublicKey::from_secret_key(&secret_key);
+    let hash = keccak256_h256(&public_key.serialize()[1..]);
+
+    Ok(H160::from_slice(&hash[12..32]))
+}
+
+/// Executes one transaction variant of the test and collects the resulting state
+pub fn run(test: &StateTest, post: &PostState, chain_id: u64) -> Result<Outcome, String> {
+    let caller = sender(test)?;
+
+    let trx = &test.transaction;
+    let data = trx.data.get(post.indexes.data).ok_or("data index out of range")?.0.clone();
+    let gas_limit = trx.gas_limit.get(post.indexes.gas).ok_or("gas index out of range")?.0;
+    let value = trx.value.get(post.indexes.value).ok_or("value index out of range")?.0;
+    let gas_price = trx.gas_price.as_ref().map_or_else(U256::zero, |price| price.0);
+
+    let mut storage = MemoryAccountStorage::new(&test.env, &test.pre, chain_id);
+
+    let (exit_reason, actions, used_gas) = {
+        let mut machine = Machine::new(caller, &storage).map_err(|e| format!("{:?}", e))?;
+
+        let begin = match trx.to {
+            Some(address) => machine.call_begin(caller, address, data, value, gas_limit, gas_price),
+            None => machine.create_begin(caller, data, value, gas_limit, gas_price).map(|_| ()),
+        };
+
+        if let Err(e) = begin {
+            // Transaction is rejected before execution, state is not changed
+            debug!("transaction rejected: {:?}", e);
+            (None, Vec::new(), U256::zero())
+        } else {
+            let (result, exit_reason) = machine.execute();
+            debug!("exit_reason={:?}, result={}", exit_reason, hex::encode(&result));
+
+            let used_gas = machine.used_gas();
+            let actions = machine.into_state_actions();
+            (Some(exit_reason), actions, used_gas)
+        }
+    };
+
+    if let Some(exit_reason) = &exit_reason {
+        if exit_reason.is_succeed() {
+            storage.apply_actions(actions);
+        } else {
+            storage.apply_actions(vec![evm_loader::executor::Action::EvmIncrementNonce { address: caller }]);
+        }
+
+        let fee = used_gas.min(gas_limit).saturating_mul(gas_price);
+        storage.transfer(&caller, &test.env.current_coinbase.0, fee);
+    }
+
+    // EIP-161: touched empty accounts are removed
+    storage.accounts.retain(|_, account| !account.is_empty());
+
+    Ok(Outcome {
+        exit_reason,
+        used_gas,
+        state_root: state_root(&storage),
+        logs_hash: logs_hash(&storage.logs),
+        accounts: storage.accounts.into_iter().collect(),
+    })
+}
+
+
+fn storage_root(account: &MemoryAccount) -> H256 {
+    let entries = account.storage.iter().map(|(key, value)| {
+        let mut key_bytes = [0_u8; 32];
+        key.to_big_endian(&mut key_bytes);
+
+        (key_bytes, rlp::encode(value).to_vec())
+    });
+
+    H256::from(triehash::sec_trie_root::<KeccakHasher, _, _, _>(entries))
+}
+
+fn state_root(storage: &MemoryAccountStorage) -> H256 {
+    let entries = storage.accounts.iter().map(|(address, account)| {
+        let mut stream = RlpStream::new_list(4);
+        stream.append(&account.nonce);
+        stream.append(&account.balance);
+        stream.append(&storage_root(account));
+        stream.append(&keccak256_h256(&account.code));
+
+        (*address, stream.out().to_vec())
+    });
+
+    H256::from(triehash::sec_trie_root::<KeccakHasher, _, _, _>(entries))
+}
+
+fn logs_hash(logs: &[Log]) -> H256 {
+    let mut stream = RlpStream::new_list(logs.len());
+    for log in logs {
+        stream.begin_list(3);
+        stream.append(&log.address);
+        stream.append_list(&log.topics);
+        stream.append(&log.data);
+    }
+
+    keccak256_h256(&stream.out())
+}
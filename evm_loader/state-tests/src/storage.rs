@@ -0,0 +1,227 @@
+use std::collections::BTreeMap;
+
+use evm::{H160, H256, U256, Valids};
+use log::warn;
+use solana_sdk::pubkey::Pubkey;
+
+use evm_loader::{
+    account::{ACCOUNT_SEED_VERSION, EthereumAccount, EthereumContract},
+    account_storage::AccountStorage,
+    executor::{Action, OwnedAccountInfo, OwnedAccountInfoPartial},
+    utils::keccak256_h256,
+};
+
+use crate::fixture::{Env, PreAccount};
+
+
+#[derive(Default, Clone)]
+pub struct MemoryAccount {
+    pub nonce: U256,
+    pub balance: U256,
+    pub code: Vec<u8>,
+    pub valids: Vec<u8>,
+    pub storage: BTreeMap<U256, U256>,
+}
+
+impl MemoryAccount {
+    /// EIP-161 empty account
+    pub fn is_empty(&self) -> bool {
+        self.nonce.is_zero() && self.balance.is_zero() && self.code.is_empty()
+    }
+}
+
+pub struct Log {
+    pub address: H160,
+    pub topics: Vec<H256>,
+    pub data: Vec<u8>,
+}
+
+/// In-memory `AccountStorage` holding the fixture pre-state
+pub struct MemoryAccountStorage {
+    program_id: Pubkey,
+    neon_token_mint: Pubkey,
+    operator: Pubkey,
+    chain_id: u64,
+    block_number: U256,
+    block_timestamp: U256,
+    previous_hash: H256,
+
+    pub accounts: BTreeMap<H160, MemoryAccount>,
+    pub logs: Vec<Log>,
+}
+
+impl MemoryAccountStorage {
+    pub fn new(env: &Env, pre: &BTreeMap<crate::fixture::Address, PreAccount>, chain_id: u64) -> Self {
+        let accounts = pre.iter()
+            .map(|(address, account)| {
+                let code = account.code.0.clone();
+                let valids = Valids::compute(&code);
+                let storage = account.storage.iter()
+                    .map(|(key, value)| (key.0, value.0))
+                    .collect();
+
+                (address.0, MemoryAccount { nonce: account.nonce.0, balance: account.balance.0, code, valids, storage })
+            })
+            .collect();
+
+        Self {
+            program_id: Pubkey::new_unique(),
+            neon_token_mint: Pubkey::new_unique(),
+            operator: Pubkey::new_unique(),
+            chain_id,
+            block_number: env.current_number.0,
+            block_timestamp: env.current_timestamp.0,
+            previous_hash: env.previous_hash.0,
+            accounts,
+            logs: Vec::new(),
+        }
+    }
+
+    fn account_map_or<F, D>(&self, address: &H160, default: D, f: F) -> D
+    where
+        F: FnOnce(&MemoryAccount) -> D
+    {
+        self.accounts.get(address).map_or(default, f)
+    }
+
+    pub fn transfer(&mut self, source: &H160, target: &H160, value: U256) {
+        let source = self.accounts.entry(*source).or_default();
+        source.balance = source.balance.saturating_sub(value);
+
+        let target = self.accounts.entry(*target).or_default();
+        target.balance = target.balance.saturating_add(value);
+    }
+
+    pub fn apply_actions(&mut self, actions: Vec<Action>) {
+        for action in actions {
+            match action {
+                Action::NeonTransfer { source, target, value } => {
+                    self.transfer(&source, &target, value);
+                },
+                Action::NeonWithdraw { source, value } => {
+                    let source = self.accounts.entry(source).or_default();
+                    source.balance = source.balance.saturating_sub(value);
+                },
+                Action::EvmLog { address, topics, data } => {
+                    self.logs.push(Log { address, topics, data });
+                },
+                Action::EvmSetStorage { address, key, value } => {
+                    let storage = &mut self.accounts.entry(address).or_default().storage;
+                    if value.is_zero() {
+                        storage.remove(&key);
+                    } else {
+                        storage.insert(key, value);
+                    }
+                },
+                Action::EvmIncrementNonce { address } => {
+                    let account = self.accounts.entry(address).or_default();
+                    account.nonce = account.nonce.saturating_add(U256::one());
+                },
+                Action::EvmSetCode { address, code, valids } => {
+                    let account = self.accounts.entry(address).or_default();
+                    account.code = code;
+                    account.valids = valids;
+                },
+                Action::EvmSelfDestruct { address } => {
+                    self.accounts.remove(&address);
+                },
+                Action::ExternalInstruction { program_id, .. } => {
+                    warn!("external instruction to {} is not supported by state tests, skipped", program_id);
+                },
+            }
+        }
+    }
+}
+
+impl AccountStorage for MemoryAccountStorage {
+    fn neon_token_mint(&self) -> &Pubkey { &self.neon_token_mint }
+
+    fn program_id(&self) -> &Pubkey { &self.program_id }
+
+    fn operator(&self) -> &Pubkey { &self.operator }
+
+    fn block_number(&self) -> U256 { self.block_number }
+
+    fn block_timestamp(&self) -> U256 { self.block_timestamp }
+
+    fn block_hash(&self, number: U256) -> H256 {
+        // Fixtures only define the hash of the previous block
+        if number.saturating_add(U256::one()) == self.block_number {
+            self.previous_hash
+        } else {
+            H256::default()
+        }
+    }
+
+    fn chain_id(&self) -> u64 { self.chain_id }
+
+    fn exists(&self, address: &H160) -> bool {
+        self.accounts.contains_key(address)
+    }
+
+    fn nonce(&self, address: &H160) -> U256 {
+        self.account_map_or(address, U256::zero(), |a| a.nonce)
+    }
+
+    fn balance(&self, address: &H160) -> U256 {
+        self.account_map_or(address, U256::zero(), |a| a.balance)
+    }
+
+    fn code_size(&self, address: &H160) -> usize {
+        self.account_map_or(address, 0, |a| a.code.len())
+    }
+
+    fn code_hash(&self, address: &H160) -> H256 {
+        self.account_map_or(address, H256::default(), |a| keccak256_h256(&a.code))
+    }
+
+    fn code(&self, address: &H160) -> Vec<u8> {
+        self.account_map_or(address, Vec::new(), |a| a.code.clone())
+    }
+
+    fn valids(&self, address: &H160) -> Vec<u8> {
+        self.account_map_or(address, Vec::new(), |a| a.valids.clone())
+    }
+
+    fn generation(&self, _address: &H160) -> u32 {
+        0
+    }
+
+    fn storage(&self, address: &H160, index: &U256) -> U256 {
+        self.account_map_or(address, U256::zero(), |a| a.storage.get(index).copied().unwrap_or_default())
+    }
+
+    fn clone_solana_account(&self, address: &Pubkey) -> OwnedAccountInfo {
+        OwnedAccountInfo {
+            key: *address,
+            is_signer: false,
+            is_writable: false,
+            lamports: 0,
+            data: vec![],
+            owner: solana_sdk::system_program::ID,
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    fn clone_solana_account_partial(&self, _address: &Pubkey, _offset: usize, _len: usize) -> Option<OwnedAccountInfoPartial> {
+        None
+    }
+
+    fn solana_address(&self, address: &H160) -> (Pubkey, u8) {
+        let seeds: &[&[u8]] = &[ &[ACCOUNT_SEED_VERSION], address.as_bytes() ];
+        Pubkey::find_program_address(seeds, self.program_id())
+    }
+
+    fn solana_accounts_space(&self, address: &H160) -> (usize, usize) {
+        self.account_map_or(address, (0, 0), |a| {
+            let contract_space = if a.code.is_empty() {
+                0
+            } else {
+                EthereumContract::SIZE + a.code.len() + a.valids.len()
+            };
+
+            (EthereumAccount::SIZE, contract_space)
+        })
+    }
+}
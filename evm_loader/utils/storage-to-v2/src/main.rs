@@ -12,9 +12,10 @@ use std::time::{Duration, Instant, SystemTime};
 use anyhow::Result;
 use arrayref::array_ref;
 use evm_core::{H160, U256};
-use evm_loader::account::{ACCOUNT_SEED_VERSION, AccountData, ether_account, ether_contract, ether_storage, Packable};
+use evm_loader::account::{AccountData, ether_account, ether_contract, ether_storage, Packable};
 use evm_loader::config::STORAGE_ENTIRIES_IN_CONTRACT_ACCOUNT;
 use evm_loader::hamt::Hamt;
+use evm_loader::instruction_builder;
 use rustc_hash::FxHashMap;
 use serde_json::{json, Value};
 use solana_account_decoder::{UiAccountEncoding, UiDataSliceConfig};
@@ -29,12 +30,10 @@ use solana_program::pubkey;
 use solana_sdk::account::{Account, ReadableAccount};
 use solana_sdk::account_info::IntoAccountInfo;
 use solana_sdk::commitment_config::CommitmentConfig;
-use solana_sdk::instruction::{AccountMeta, Instruction};
 use solana_sdk::message::Message;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::signer::keypair::{Keypair, read_keypair_file};
 use solana_sdk::signer::Signer;
-use solana_sdk::system_program;
 use solana_sdk::transaction::Transaction;
 use solana_transaction_status::UiTransactionEncoding;
 
@@ -228,49 +227,8 @@ impl<'url> Batch<'url> {
     }
 }
 
-fn write_value_instruction(
-    ether_account: Pubkey,
-    storage_address: Pubkey,
-    key: U256,
-    value: U256,
-) -> Instruction {
-    let mut data = vec![28_u8; 1 + 32 + 32];
-    key.to_big_endian(&mut data[1..33]);
-    value.to_big_endian(&mut data[33..]);
-
-    Instruction::new_with_bytes(
-        *EVM_LOADER,
-        &data,
-        vec![
-            AccountMeta::new_readonly(PAYER.pubkey(), true),         // Operator
-            AccountMeta::new_readonly(system_program::id(), false),  // System program
-            AccountMeta::new_readonly(ether_account, false),         // Ether account
-            AccountMeta::new(storage_address, false),                // Storage account
-        ],
-    )
-}
-
-fn convert_to_v2_instruction(
-    ether_account: Pubkey,
-) -> Instruction {
-    Instruction::new_with_bytes(
-        *EVM_LOADER,
-        &[29u8],
-        vec![
-            AccountMeta::new_readonly(PAYER.pubkey(), true),         // Funding account
-            AccountMeta::new_readonly(system_program::id(), false),  // System program
-            AccountMeta::new(ether_account, false),                  // Ether account
-        ],
-    )
-}
-
 fn get_storage_address(address: &H160, index: &U256) -> Pubkey {
-    let mut index_bytes = [0_u8; 32];
-    index.to_little_endian(&mut index_bytes);
-
-    let seeds: &[&[u8]] = &[&[ACCOUNT_SEED_VERSION], b"ContractStorage", address.as_bytes(), &[0; size_of::<u32>()], &index_bytes];
-
-    Pubkey::find_program_address(seeds, &EVM_LOADER).0
+    instruction_builder::storage_address(&EVM_LOADER, address, 0, index)
 }
 
 fn get_evm_accounts(
@@ -324,7 +282,13 @@ fn copy_data_to_distributed_storage<'a>(
         }
 
         let instructions = vec![
-            write_value_instruction(*ethereum_contract_v1.owner, storage_address, key, value),
+            instruction_builder::write_value_to_distributed_storage(
+                &EVM_LOADER,
+                &PAYER.pubkey(),
+                &ethereum_contract_v1.ether_address,
+                &key,
+                &value,
+            ),
         ];
         let blockhash = recent_blockhash.get();
         let mut message = Message::new(&instructions, Some(&PAYER.pubkey()));
@@ -392,7 +356,7 @@ fn extract_data_to_distributed_storage(
 
 fn make_convert_to_v2_transaction(pubkey: Pubkey, recent_blockhash: &Hash) -> Transaction {
     let instructions = vec![
-        convert_to_v2_instruction(pubkey),
+        instruction_builder::convert_data_account_from_v1_to_v2(&EVM_LOADER, &PAYER.pubkey(), &pubkey),
     ];
     let mut message = Message::new(&instructions, Some(&PAYER.pubkey()));
     message.recent_blockhash = *recent_blockhash;